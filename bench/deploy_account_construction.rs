@@ -0,0 +1,83 @@
+/*
+Usage:
+    `cargo bench --bench deploy_account_construction`
+
+Compares building every deploy account candidate eagerly, computing its transaction hash
+upfront, against building them unhashed and only computing the hash of the candidates that
+survive a cheap check (here, one in ten).
+*/
+#![deny(warnings)]
+
+use cairo_vm::Felt252;
+use starknet_in_rust::{
+    definitions::block_context::StarknetChainId,
+    transaction::{
+        deploy_account::DeployAccountUnhashed, DeployAccount, VersionSpecificAccountTxFields,
+    },
+    utils::ClassHash,
+};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const CANDIDATES: u64 = 10_000;
+const KEPT_ONE_IN: u64 = 10;
+
+fn main() {
+    let chain_id = StarknetChainId::TestNet.to_felt();
+    let candidates: Vec<DeployAccountUnhashed> = (0..CANDIDATES)
+        .map(|salt| DeployAccountUnhashed {
+            class_hash: ClassHash([1; 32]),
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(1_000_000),
+            version: Felt252::ONE,
+            nonce: Felt252::ZERO,
+            constructor_calldata: vec![2.into()],
+            signature: vec![3.into(), 4.into()],
+            contract_address_salt: salt.into(),
+        })
+        .collect();
+
+    let eager = time(|| {
+        candidates
+            .iter()
+            .cloned()
+            .enumerate()
+            .filter_map(|(i, candidate)| {
+                let deploy_account = DeployAccount::new(
+                    candidate.class_hash,
+                    candidate.account_tx_fields,
+                    candidate.version,
+                    candidate.nonce,
+                    candidate.constructor_calldata,
+                    candidate.signature,
+                    candidate.contract_address_salt,
+                    chain_id,
+                )
+                .unwrap();
+                (i as u64 % KEPT_ONE_IN == 0).then_some(deploy_account)
+            })
+            .count()
+    });
+
+    let lazy = time(|| {
+        candidates
+            .iter()
+            .cloned()
+            .enumerate()
+            .filter_map(|(i, candidate)| {
+                black_box(candidate.contract_address().unwrap());
+                (i as u64 % KEPT_ONE_IN == 0).then(|| candidate.finalize(chain_id).unwrap())
+            })
+            .count()
+    });
+
+    println!("eager construction of {CANDIDATES} candidates: {eager:?}");
+    println!("lazy construction of {CANDIDATES} candidates: {lazy:?}");
+}
+
+fn time(f: impl FnOnce() -> usize) -> Duration {
+    let start = Instant::now();
+    black_box(f());
+    start.elapsed()
+}
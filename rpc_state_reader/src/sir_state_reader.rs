@@ -198,6 +198,7 @@ pub fn execute_tx_configurable_with_state(
         SNTransaction::DeployAccount(tx) => DeployAccount::from_sn_api_transaction(
             tx,
             Felt252::from_bytes_be_slice(tx_hash.0.bytes()),
+            false,
         )
         .unwrap()
         .create_for_simulation(skip_validate, false, false, false, skip_nonce_check),
@@ -2,12 +2,19 @@
 //!
 //! This module contains structs representing the context of a specific Starknet block.
 
-use crate::{state::BlockInfo, utils::Address};
+use crate::{
+    execution::{
+        os_usage::{OsResources, DEFAULT_OS_RESOURCES},
+        Builtin,
+    },
+    state::BlockInfo,
+    utils::Address,
+};
 use cairo_vm::Felt252;
 use core::fmt;
-use getset::{CopyGetters, Getters, MutGetters};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
 use starknet_api::block::Block;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::constants::{
     DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS, DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
@@ -121,7 +128,7 @@ impl FeeTokenAddresses {
     }
 }
 
-#[derive(Clone, Copy, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
 pub enum FeeType {
     Strk,
     Eth,
@@ -155,7 +162,7 @@ impl Default for StarknetOsConfig {
 }
 
 /// Starknet block context.
-#[derive(Clone, Debug, CopyGetters, Getters, MutGetters)]
+#[derive(Clone, Debug, CopyGetters, Getters, MutGetters, Setters)]
 pub struct BlockContext {
     #[getset(get = "pub", get_mut = "pub")]
     pub(crate) starknet_os_config: StarknetOsConfig,
@@ -175,6 +182,29 @@ pub struct BlockContext {
     #[getset(get = "pub", get_mut = "pub")]
     pub(crate) blocks: HashMap<u64, Block>,
     pub(crate) enforce_l1_handler_fee: bool,
+    /// Whether an account balance that can't cover the max fee reverts the transaction instead
+    /// of failing its execution.
+    #[getset(get_copy = "pub", set = "pub")]
+    pub(crate) insufficient_balance_reverts: bool,
+    /// OS resources table overriding the compiled one, e.g. to replay blocks of protocol
+    /// versions with different OS step costs.
+    pub(crate) os_resources: Option<OsResources>,
+    /// Maximum number of events a transaction may emit across its call tree, if limited.
+    /// Transactions emitting more are reverted, still paying their fee.
+    #[getset(get_copy = "pub", set = "pub")]
+    pub(crate) max_events: Option<usize>,
+    /// Whether the account nonce is incremented after the transaction is executed rather than
+    /// before, so that a failed execution leaves it untouched.
+    #[getset(get_copy = "pub", set = "pub")]
+    pub(crate) increment_nonce_after_execution: bool,
+    /// Builtins the classes executed in the block may use, if restricted.
+    #[getset(get = "pub", set = "pub")]
+    pub(crate) allowed_builtins: Option<HashSet<Builtin>>,
+    /// Whether transactions are rejected when the ETH and STRK fee token addresses are the
+    /// same, which would mix up the balances of both tokens. Enforced by default; it can be
+    /// disabled for setups that knowingly share a single fee token.
+    #[getset(get_copy = "pub", set = "pub")]
+    pub(crate) reject_ambiguous_fee_tokens: bool,
 }
 
 impl BlockContext {
@@ -214,8 +244,24 @@ impl BlockContext {
             block_info,
             blocks,
             enforce_l1_handler_fee,
+            insufficient_balance_reverts: false,
+            os_resources: None,
+            max_events: None,
+            increment_nonce_after_execution: false,
+            allowed_builtins: None,
+            reject_ambiguous_fee_tokens: true,
         }
     }
+
+    /// Returns the OS resources table used to compute the OS overhead of transactions.
+    pub fn os_resources(&self) -> &OsResources {
+        self.os_resources.as_ref().unwrap_or(&DEFAULT_OS_RESOURCES)
+    }
+
+    /// Replaces the OS resources table used to compute the OS overhead of transactions.
+    pub fn set_os_resources(&mut self, os_resources: OsResources) {
+        self.os_resources = Some(os_resources);
+    }
 }
 
 impl Default for BlockContext {
@@ -231,6 +277,12 @@ impl Default for BlockContext {
             block_info: BlockInfo::empty(DEFAULT_SEQUENCER_ADDRESS.clone()),
             blocks: HashMap::default(),
             enforce_l1_handler_fee: true,
+            insufficient_balance_reverts: false,
+            os_resources: None,
+            max_events: None,
+            increment_nonce_after_execution: false,
+            allowed_builtins: None,
+            reject_ambiguous_fee_tokens: true,
         }
     }
 }
@@ -104,6 +104,11 @@ lazy_static! {
         Felt252::from_dec_str("370462705988").unwrap();
 
     pub static ref BLOCK_HASH_CONTRACT_ADDRESS: Address = Address(1.into());
+
+    /// Short string `'L1_GAS'`, naming the L1 gas resource bounds in the tx info.
+    pub static ref L1_GAS: Felt252 = Felt252::from_bytes_be_slice(b"L1_GAS");
+    /// Short string `'L2_GAS'`, naming the L2 gas resource bounds in the tx info.
+    pub static ref L2_GAS: Felt252 = Felt252::from_bytes_be_slice(b"L2_GAS");
 }
 
 // Indentation for transactions meant to query and not addressed to the OS.
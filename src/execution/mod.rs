@@ -5,21 +5,44 @@ use crate::services::api::contract_classes::deprecated_contract_class::EntryPoin
 use crate::transaction::VersionSpecificAccountTxFields;
 use crate::utils::parse_felt_array;
 use crate::{
-    definitions::{constants::CONSTRUCTOR_ENTRY_POINT_SELECTOR, transaction_type::TransactionType},
-    state::state_cache::StorageEntry,
-    syscalls::syscall_handler_errors::SyscallHandlerError,
-    transaction::error::TransactionError,
+    definitions::{
+        block_context::{BlockContext, FeeType},
+        constants::{CONSTRUCTOR_ENTRY_POINT_SELECTOR, TRANSFER_EVENT_SELECTOR},
+        transaction_type::TransactionType,
+    },
+    state::{state_cache::StorageEntry, ContractLeafUpdate, StateDiff},
+    syscalls::{
+        business_logic_syscall_handler::SYSCALL_GAS_COST,
+        syscall_handler_errors::SyscallHandlerError,
+    },
+    transaction::{error::TransactionError, fee::max_of_keys},
     utils::{get_big_int, get_integer, get_relocatable, Address, ClassHash},
 };
 use cairo_vm::Felt252;
 use cairo_vm::{
     types::relocatable::{MaybeRelocatable, Relocatable},
-    vm::{runners::cairo_runner::ExecutionResources, vm_core::VirtualMachine},
+    vm::{
+        runners::{
+            builtin_runner::{
+                BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+                OUTPUT_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME,
+                SEGMENT_ARENA_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
+            },
+            cairo_runner::ExecutionResources,
+        },
+        vm_core::VirtualMachine,
+    },
 };
 use getset::Getters;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Deserializer};
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::{self, Write},
+};
+
+#[cfg(feature = "timings")]
+use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CallType {
@@ -212,6 +235,42 @@ impl CallInfo {
         })
     }
 
+    /// Writes a human-readable representation of the call and its internal calls into `out`,
+    /// indented according to their depth in the call tree.
+    fn write_call_tree(&self, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        let fmt_felts = |felts: &[Felt252]| {
+            felts
+                .iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let _ = writeln!(
+            out,
+            "{indent}call contract: {}, selector: {}",
+            self.contract_address.0,
+            self.entry_point_selector
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "None".to_string())
+        );
+        let _ = writeln!(out, "{indent}  calldata: [{}]", fmt_felts(&self.calldata));
+        let _ = writeln!(out, "{indent}  retdata: [{}]", fmt_felts(&self.retdata));
+        for event in self.events.iter() {
+            let _ = writeln!(
+                out,
+                "{indent}  event #{} keys: [{}], data: [{}]",
+                event.order,
+                fmt_felts(&event.keys),
+                fmt_felts(&event.data)
+            );
+        }
+        for inner_call in self.internal_calls.iter() {
+            inner_call.write_call_tree(depth + 1, out);
+        }
+    }
+
     pub fn result(&self) -> CallResult {
         CallResult {
             gas_consumed: self.gas_consumed,
@@ -493,7 +552,7 @@ impl TxInfoStruct {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default)]
 pub struct TransactionExecutionInfo {
     pub validate_info: Option<CallInfo>,
     pub call_info: Option<CallInfo>,
@@ -502,6 +561,47 @@ pub struct TransactionExecutionInfo {
     pub actual_fee: u128,
     pub actual_resources: HashMap<String, usize>,
     pub tx_type: Option<TransactionType>,
+    /// Amount by which the calculated fee exceeded the max fee, when the transaction was allowed
+    /// to complete instead of being reverted.
+    pub fee_overage: Option<u128>,
+    /// Change in the sequencer's fee token balance caused by charging the fee, when requested
+    /// by the transaction.
+    pub sequencer_balance_delta: Option<Felt252>,
+    /// Leaf updates of the state commitment caused by the transaction's applied state diff,
+    /// when requested by the transaction.
+    pub commitment_leaf_updates: Option<HashMap<Address, ContractLeafUpdate>>,
+    /// Storage reads and writes performed by the transaction's calls, excluding the fee
+    /// transfer, when requested by the transaction.
+    pub storage_access_counts: Option<StorageAccessCounts>,
+    /// Token the fee was charged in, when reported by the transaction.
+    pub fee_paid_in: Option<FeeType>,
+    /// State diff as of after the constructor ran and before the validation, when requested by
+    /// the transaction.
+    pub post_constructor_state_diff: Option<StateDiff>,
+    /// Gas the transaction's syscalls cost, by syscall, when requested by the transaction.
+    pub syscall_gas: Option<HashMap<Syscall, u128>>,
+    #[cfg(feature = "timings")]
+    pub phase_timings: PhaseTimings,
+}
+
+// Timings vary from one run to the next, so `phase_timings` is left out of the comparison.
+impl PartialEq for TransactionExecutionInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.validate_info == other.validate_info
+            && self.call_info == other.call_info
+            && self.revert_error == other.revert_error
+            && self.fee_transfer_info == other.fee_transfer_info
+            && self.actual_fee == other.actual_fee
+            && self.actual_resources == other.actual_resources
+            && self.tx_type == other.tx_type
+            && self.fee_overage == other.fee_overage
+            && self.sequencer_balance_delta == other.sequencer_balance_delta
+            && self.commitment_leaf_updates == other.commitment_leaf_updates
+            && self.storage_access_counts == other.storage_access_counts
+            && self.fee_paid_in == other.fee_paid_in
+            && self.post_constructor_state_diff == other.post_constructor_state_diff
+            && self.syscall_gas == other.syscall_gas
+    }
 }
 
 impl TransactionExecutionInfo {
@@ -522,6 +622,15 @@ impl TransactionExecutionInfo {
             actual_fee,
             actual_resources,
             tx_type,
+            fee_overage: None,
+            sequencer_balance_delta: None,
+            commitment_leaf_updates: None,
+            storage_access_counts: None,
+            fee_paid_in: None,
+            post_constructor_state_diff: None,
+            syscall_gas: None,
+            #[cfg(feature = "timings")]
+            phase_timings: PhaseTimings::new(),
         }
     }
 
@@ -543,6 +652,30 @@ impl TransactionExecutionInfo {
         calls.into_iter().flatten().collect()
     }
 
+    /// Returns an iterator over every call of the transaction, including the inner calls, in DFS
+    /// (preorder) and in execution order: for deploy transactions the constructor runs before
+    /// the validation.
+    pub fn iter_calls(&self) -> impl Iterator<Item = &CallInfo> {
+        let roots = match self.tx_type {
+            Some(TransactionType::Deploy | TransactionType::DeployAccount) => [
+                &self.call_info,
+                &self.validate_info,
+                &self.fee_transfer_info,
+            ],
+            _ => [
+                &self.validate_info,
+                &self.call_info,
+                &self.fee_transfer_info,
+            ],
+        };
+        let mut stack: Vec<&CallInfo> = roots.into_iter().rev().flatten().collect();
+        std::iter::from_fn(move || {
+            let call = stack.pop()?;
+            stack.extend(call.internal_calls.iter().rev());
+            Some(call)
+        })
+    }
+
     pub fn get_visited_storage_entries(&self) -> HashSet<StorageEntry> {
         CallInfo::get_visited_storage_entries_of_many(self.non_optional_calls())
     }
@@ -561,6 +694,15 @@ impl TransactionExecutionInfo {
             actual_fee: 0,
             actual_resources: HashMap::new(),
             tx_type,
+            fee_overage: None,
+            sequencer_balance_delta: None,
+            commitment_leaf_updates: None,
+            storage_access_counts: None,
+            fee_paid_in: None,
+            post_constructor_state_diff: None,
+            syscall_gas: None,
+            #[cfg(feature = "timings")]
+            phase_timings: PhaseTimings::new(),
         }
     }
 
@@ -579,6 +721,15 @@ impl TransactionExecutionInfo {
             actual_fee: 0,
             actual_resources,
             tx_type,
+            fee_overage: None,
+            sequencer_balance_delta: None,
+            commitment_leaf_updates: None,
+            storage_access_counts: None,
+            fee_paid_in: None,
+            post_constructor_state_diff: None,
+            syscall_gas: None,
+            #[cfg(feature = "timings")]
+            phase_timings: PhaseTimings::new(),
         }
     }
 
@@ -623,6 +774,216 @@ impl TransactionExecutionInfo {
         Ok(sorted_messages)
     }
 
+    /// Returns the builtin instance counts of `actual_resources`, keyed by builtin.
+    pub fn builtin_usage(&self) -> HashMap<Builtin, usize> {
+        self.actual_resources
+            .iter()
+            .filter_map(|(name, count)| Some((Builtin::from_resource_name(name)?, *count)))
+            .collect()
+    }
+
+    /// Returns the gas consumed by the validate entry point call, if it was run.
+    pub fn validate_gas_consumed(&self) -> Option<u128> {
+        self.validate_info
+            .as_ref()
+            .map(|validate_info| validate_info.gas_consumed)
+    }
+
+    /// Returns the calldata the constructor was executed with, for deploy transactions.
+    pub fn constructor_calldata_executed(&self) -> Option<&[Felt252]> {
+        match self.tx_type {
+            Some(TransactionType::Deploy | TransactionType::DeployAccount) => self
+                .call_info
+                .as_ref()
+                .map(|call_info| call_info.calldata.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Returns the sender, the recipient and the amount of the ERC-20 `Transfer` event emitted
+    /// by the fee transfer, if the fee was charged.
+    pub fn fee_transfer_event(&self) -> Option<(Address, Address, u128)> {
+        let event = self
+            .fee_transfer_info
+            .as_ref()?
+            .events
+            .iter()
+            .find(|event| event.keys.first() == Some(&*TRANSFER_EVENT_SELECTOR))?;
+        // The amount is a Uint256, split into its low and high 128 bit words.
+        let [from, to, amount_low, amount_high] = event.data[..] else {
+            return None;
+        };
+        if amount_high != Felt252::ZERO {
+            return None;
+        }
+
+        Some((Address(from), Address(to), amount_low.to_u128()?))
+    }
+
+    /// Returns a human-readable dump of the call trees of the transaction's phases, in the order
+    /// they ran, including selectors, calldata, retdata, events and nested calls.
+    ///
+    /// A deploy account transaction runs its constructor before validating, so its phases are
+    /// dumped as constructor, validate and fee transfer; other transactions are dumped as
+    /// validate, execute and fee transfer.
+    pub fn format_call_tree(&self) -> String {
+        let is_constructor = self.call_info.as_ref().is_some_and(|call_info| {
+            call_info.entry_point_type == Some(EntryPointType::Constructor)
+        });
+        let phases = if is_constructor {
+            [
+                ("constructor", &self.call_info),
+                ("validate", &self.validate_info),
+                ("fee transfer", &self.fee_transfer_info),
+            ]
+        } else {
+            [
+                ("validate", &self.validate_info),
+                ("execute", &self.call_info),
+                ("fee transfer", &self.fee_transfer_info),
+            ]
+        };
+
+        let mut out = String::new();
+        for (name, call_info) in phases {
+            if let Some(call_info) = call_info {
+                let _ = writeln!(out, "{name}:");
+                call_info.write_call_tree(1, &mut out);
+            }
+        }
+        out
+    }
+
+    /// Returns the actual resources sorted by name, so that iterating them (e.g. to log or
+    /// serialize them) doesn't depend on the map's ordering.
+    pub fn sorted_resources(&self) -> Vec<(&str, usize)> {
+        let mut resources: Vec<_> = self
+            .actual_resources
+            .iter()
+            .map(|(name, amount)| (name.as_str(), *amount))
+            .collect();
+        resources.sort_unstable();
+        resources
+    }
+
+    /// Returns the total L1 gas attributable to the transaction: the L1 gas of its onchain data
+    /// plus its Cairo resources converted to L1 gas with the block's fee weights, rounded up as
+    /// when computing the fee.
+    pub fn total_l1_gas(&self, block_context: &BlockContext) -> u128 {
+        let data_gas = self
+            .actual_resources
+            .get("l1_gas_usage")
+            .copied()
+            .unwrap_or(0) as u128;
+        let compute_gas = max_of_keys(
+            &self.actual_resources,
+            &block_context.cairo_resource_fee_weights,
+        )
+        .ceil() as u128;
+        data_gas + compute_gas
+    }
+
+    /// Maps a deploy account execution into the shape of the Starknet trace RPC, where
+    /// `call_info` is the constructor invocation.
+    pub fn to_deploy_account_trace(&self) -> DeployAccountTrace {
+        DeployAccountTrace {
+            validate_invocation: self.validate_info.as_ref().map(Into::into),
+            constructor_invocation: self.call_info.as_ref().map(Into::into),
+            fee_transfer_invocation: self.fee_transfer_info.as_ref().map(Into::into),
+            execution_resources: self.actual_resources.clone(),
+        }
+    }
+
+    /// Maps a deploy account execution into the shape of a `DEPLOY_ACCOUNT_TXN_RECEIPT` of the
+    /// Starknet RPC. The deployed address is taken from the transaction rather than from
+    /// `call_info`, which is `None` when the transaction reverted.
+    pub fn to_deploy_account_receipt(
+        &self,
+        transaction_hash: Felt252,
+        contract_address: Address,
+    ) -> Result<DeployAccountReceipt, TransactionError> {
+        Ok(DeployAccountReceipt {
+            transaction_hash,
+            actual_fee: self.actual_fee,
+            execution_status: match &self.revert_error {
+                Some(revert_reason) => ExecutionStatus::Reverted(revert_reason.clone()),
+                None => ExecutionStatus::Succeeded,
+            },
+            events: self.get_sorted_events()?,
+            messages_sent: self.get_sorted_l2_to_l1_messages()?,
+            contract_address,
+        })
+    }
+
+    /// Returns a compact summary of the execution, meant for logging.
+    pub fn summary(&self) -> ExecSummary {
+        ExecSummary {
+            actual_fee: self.actual_fee,
+            reverted: self.revert_error.is_some(),
+            n_steps: self.actual_resources.get("n_steps").copied().unwrap_or(0),
+            revert_reason: self.revert_error.as_deref().map(short_revert_reason),
+        }
+    }
+
+    /// Compares two execution infos and returns the divergences in fee, revert error, resources,
+    /// retdata and events of the validate, execute and fee transfer calls.
+    /// Resources missing from one side count as zero, and events are compared in emission order,
+    /// so neither the map ordering nor the call nesting produce spurious differences.
+    pub fn diff(&self, other: &TransactionExecutionInfo) -> Vec<ExecInfoDiff> {
+        let mut diffs = Vec::new();
+
+        if self.actual_fee != other.actual_fee {
+            diffs.push(ExecInfoDiff::ActualFee(self.actual_fee, other.actual_fee));
+        }
+        if self.revert_error != other.revert_error {
+            diffs.push(ExecInfoDiff::RevertError(
+                self.revert_error.clone(),
+                other.revert_error.clone(),
+            ));
+        }
+
+        let resources: BTreeSet<&String> = self
+            .actual_resources
+            .keys()
+            .chain(other.actual_resources.keys())
+            .collect();
+        for resource in resources {
+            let left = self.actual_resources.get(resource).copied().unwrap_or(0);
+            let right = other.actual_resources.get(resource).copied().unwrap_or(0);
+            if left != right {
+                diffs.push(ExecInfoDiff::ActualResource(resource.clone(), left, right));
+            }
+        }
+
+        for (name, left, right) in [
+            ("validate", &self.validate_info, &other.validate_info),
+            ("execute", &self.call_info, &other.call_info),
+            (
+                "fee transfer",
+                &self.fee_transfer_info,
+                &other.fee_transfer_info,
+            ),
+        ] {
+            let left_retdata = left.as_ref().map(|call_info| &call_info.retdata);
+            let right_retdata = right.as_ref().map(|call_info| &call_info.retdata);
+            if left_retdata != right_retdata {
+                diffs.push(ExecInfoDiff::Retdata(
+                    name,
+                    left_retdata.cloned(),
+                    right_retdata.cloned(),
+                ));
+            }
+
+            let left_events = events_in_emission_order(left.as_ref());
+            let right_events = events_in_emission_order(right.as_ref());
+            if left_events != right_events {
+                diffs.push(ExecInfoDiff::Events(name, left_events, right_events));
+            }
+        }
+
+        diffs
+    }
+
     pub fn to_revert_error(self, revert_error: &str) -> Self {
         TransactionExecutionInfo {
             validate_info: None,
@@ -634,6 +995,212 @@ impl TransactionExecutionInfo {
     }
 }
 
+/// Numbers of storage accesses performed by the calls of a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StorageAccessCounts {
+    /// Number of storage reads, one per read syscall.
+    pub reads: usize,
+    /// Number of distinct storage keys written.
+    pub writes: usize,
+}
+
+/// Wall-clock duration of the execution phases of a transaction, for the phases it ran.
+#[cfg(feature = "timings")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    pub validate: Option<Duration>,
+    pub constructor: Option<Duration>,
+}
+
+#[cfg(feature = "timings")]
+impl PhaseTimings {
+    pub const fn new() -> Self {
+        Self {
+            validate: None,
+            constructor: None,
+        }
+    }
+}
+
+/// A Cairo builtin, as counted in the execution resources.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Builtin {
+    Output,
+    Pedersen,
+    RangeCheck,
+    Ecdsa,
+    Bitwise,
+    EcOp,
+    Keccak,
+    Poseidon,
+    SegmentArena,
+}
+
+impl Builtin {
+    /// Parses the name a builtin is counted under, such as `pedersen_builtin`.
+    pub fn from_resource_name(name: &str) -> Option<Self> {
+        Some(match name {
+            OUTPUT_BUILTIN_NAME => Self::Output,
+            HASH_BUILTIN_NAME => Self::Pedersen,
+            RANGE_CHECK_BUILTIN_NAME => Self::RangeCheck,
+            SIGNATURE_BUILTIN_NAME => Self::Ecdsa,
+            BITWISE_BUILTIN_NAME => Self::Bitwise,
+            EC_OP_BUILTIN_NAME => Self::EcOp,
+            KECCAK_BUILTIN_NAME => Self::Keccak,
+            POSEIDON_BUILTIN_NAME => Self::Poseidon,
+            SEGMENT_ARENA_BUILTIN_NAME => Self::SegmentArena,
+            _ => return None,
+        })
+    }
+}
+
+/// A syscall priced in the syscall gas schedule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Syscall {
+    CallContract,
+    Deploy,
+    EmitEvent,
+    GetBlockHash,
+    GetBlockTimestamp,
+    GetExecutionInfo,
+    Keccak,
+    LibraryCall,
+    ReplaceClass,
+    SendMessageToL1,
+    StorageRead,
+    StorageWrite,
+}
+
+impl Syscall {
+    const ALL: [Self; 12] = [
+        Self::CallContract,
+        Self::Deploy,
+        Self::EmitEvent,
+        Self::GetBlockHash,
+        Self::GetBlockTimestamp,
+        Self::GetExecutionInfo,
+        Self::Keccak,
+        Self::LibraryCall,
+        Self::ReplaceClass,
+        Self::SendMessageToL1,
+        Self::StorageRead,
+        Self::StorageWrite,
+    ];
+
+    /// Returns the name the syscall is counted under, such as `storage_read`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::CallContract => "call_contract",
+            Self::Deploy => "deploy",
+            Self::EmitEvent => "emit_event",
+            Self::GetBlockHash => "get_block_hash",
+            Self::GetBlockTimestamp => "get_block_timestamp",
+            Self::GetExecutionInfo => "get_execution_info",
+            Self::Keccak => "keccak",
+            Self::LibraryCall => "library_call",
+            Self::ReplaceClass => "replace_class",
+            Self::SendMessageToL1 => "send_message_to_l1",
+            Self::StorageRead => "storage_read",
+            Self::StorageWrite => "storage_write",
+        }
+    }
+
+    /// Parses the name a syscall is counted under, such as `storage_read`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|syscall| syscall.name() == name)
+    }
+
+    /// Returns the gas an invocation of the syscall costs, excluding the gas of the entry point
+    /// it may call, which is accounted to the callee.
+    pub fn gas_cost(self) -> u128 {
+        let gas_cost = SYSCALL_GAS_COST[self.name()];
+        match self {
+            Self::CallContract | Self::Deploy | Self::LibraryCall => {
+                gas_cost - SYSCALL_GAS_COST["entry_point"]
+            }
+            _ => gas_cost,
+        }
+    }
+}
+
+/// A compact summary of a [`TransactionExecutionInfo`], as returned by
+/// [`TransactionExecutionInfo::summary`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecSummary {
+    pub actual_fee: u128,
+    pub reverted: bool,
+    pub n_steps: usize,
+    /// The first line of the revert error, truncated to [`ExecSummary::MAX_REVERT_REASON_LEN`]
+    /// characters.
+    pub revert_reason: Option<String>,
+}
+
+impl ExecSummary {
+    pub const MAX_REVERT_REASON_LEN: usize = 64;
+}
+
+impl fmt::Display for ExecSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "fee: {}, reverted: {}, n_steps: {}",
+            self.actual_fee, self.reverted, self.n_steps
+        )?;
+        if let Some(revert_reason) = &self.revert_reason {
+            write!(f, ", revert reason: {revert_reason}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Shortens a revert error to its first line, capped at [`ExecSummary::MAX_REVERT_REASON_LEN`]
+/// characters. An ellipsis marks that something was cut.
+fn short_revert_reason(revert_error: &str) -> String {
+    let first_line = revert_error.lines().next().unwrap_or_default();
+    let mut reason: String = first_line
+        .chars()
+        .take(ExecSummary::MAX_REVERT_REASON_LEN)
+        .collect();
+    if reason.len() < revert_error.trim_end().len() {
+        reason.push_str("...");
+    }
+    reason
+}
+
+/// A divergence between two [`TransactionExecutionInfo`]s, as reported by
+/// [`TransactionExecutionInfo::diff`]. Values are given as (self, other).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecInfoDiff {
+    ActualFee(u128, u128),
+    RevertError(Option<String>, Option<String>),
+    ActualResource(String, usize, usize),
+    /// Retdata of the named call: "validate", "execute" or "fee transfer".
+    Retdata(&'static str, Option<Vec<Felt252>>, Option<Vec<Felt252>>),
+    /// Events emitted during the named call and its internal calls, in emission order.
+    Events(&'static str, Vec<Event>, Vec<Event>),
+}
+
+/// Collects the events of a call and its internal calls, sorted by emission order.
+/// Unlike [`CallInfo::get_sorted_events`], it doesn't fail on holes in the ordering.
+fn events_in_emission_order(call_info: Option<&CallInfo>) -> Vec<Event> {
+    let mut events: Vec<(u64, Event)> = call_info
+        .map(CallInfo::gen_call_topology)
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|call| {
+            let contract_address = call.contract_address;
+            call.events.into_iter().map(move |ordered_event| {
+                (
+                    ordered_event.order,
+                    Event::new(ordered_event, contract_address.clone()),
+                )
+            })
+        })
+        .collect();
+    events.sort_by_key(|(order, _)| *order);
+    events.into_iter().map(|(_, event)| event).collect()
+}
+
 // --------------------
 // Messages Structures
 // --------------------
@@ -682,6 +1249,78 @@ impl L2toL1MessageInfo {
     }
 }
 
+// ------------------
+//  Trace Structures
+// ------------------
+
+/// A call shaped as a `FUNCTION_INVOCATION` of the Starknet trace RPC.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionInvocation {
+    pub contract_address: Address,
+    pub entry_point_selector: Option<Felt252>,
+    pub calldata: Vec<Felt252>,
+    pub caller_address: Address,
+    pub class_hash: Option<ClassHash>,
+    pub entry_point_type: Option<EntryPointType>,
+    pub call_type: Option<CallType>,
+    pub result: Vec<Felt252>,
+    pub calls: Vec<FunctionInvocation>,
+    pub events: Vec<OrderedEvent>,
+    pub messages: Vec<OrderedL2ToL1Message>,
+    pub execution_resources: Option<ExecutionResources>,
+}
+
+impl From<&CallInfo> for FunctionInvocation {
+    fn from(call_info: &CallInfo) -> Self {
+        FunctionInvocation {
+            contract_address: call_info.contract_address.clone(),
+            entry_point_selector: call_info.entry_point_selector,
+            calldata: call_info.calldata.clone(),
+            caller_address: call_info.caller_address.clone(),
+            class_hash: call_info.class_hash,
+            entry_point_type: call_info.entry_point_type,
+            call_type: call_info.call_type.clone(),
+            result: call_info.retdata.clone(),
+            calls: call_info.internal_calls.iter().map(Into::into).collect(),
+            events: call_info.events.clone(),
+            messages: call_info.l2_to_l1_messages.clone(),
+            execution_resources: call_info.execution_resources.clone(),
+        }
+    }
+}
+
+/// A deploy account execution shaped as a `DEPLOY_ACCOUNT_TXN_TRACE` of the Starknet trace RPC,
+/// as built by [`TransactionExecutionInfo::to_deploy_account_trace`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeployAccountTrace {
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub constructor_invocation: Option<FunctionInvocation>,
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    /// The resources of the whole transaction, including the OS overhead.
+    pub execution_resources: HashMap<String, usize>,
+}
+
+/// Execution status of a transaction, as reported in its receipt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionStatus {
+    Succeeded,
+    /// The transaction was reverted, with the revert reason.
+    Reverted(String),
+}
+
+/// A deploy account execution shaped as a `DEPLOY_ACCOUNT_TXN_RECEIPT` of the Starknet RPC,
+/// as built by [`TransactionExecutionInfo::to_deploy_account_receipt`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeployAccountReceipt {
+    pub transaction_hash: Felt252,
+    pub actual_fee: u128,
+    pub execution_status: ExecutionStatus,
+    pub events: Vec<Event>,
+    pub messages_sent: Vec<L2toL1MessageInfo>,
+    /// The address of the deployed account.
+    pub contract_address: Address,
+}
+
 // ---------------
 //     Tests
 // ---------------
@@ -689,7 +1328,11 @@ impl L2toL1MessageInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::{string_to_hash, Address};
+    use crate::{
+        definitions::block_context::{FeeType, GasPrices},
+        transaction::fee::calculate_tx_fee,
+        utils::{string_to_hash, Address},
+    };
 
     #[test]
     fn test_get_sorted_single_event() {
@@ -745,6 +1388,184 @@ mod tests {
         assert_eq!(res, [])
     }
 
+    #[test]
+    fn sorted_resources_test() {
+        let names = [
+            "pedersen_builtin",
+            "n_steps",
+            "l1_gas_usage",
+            "range_check_builtin",
+        ];
+        let tx_info = |names: &[&str]| TransactionExecutionInfo {
+            actual_resources: names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.to_string(), name.len() * 10 + i))
+                .collect(),
+            ..Default::default()
+        };
+
+        let sorted = tx_info(&names).sorted_resources();
+        assert_eq!(
+            sorted.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            vec![
+                "l1_gas_usage",
+                "n_steps",
+                "pedersen_builtin",
+                "range_check_builtin"
+            ]
+        );
+        assert_eq!(sorted[1], ("n_steps", 71));
+
+        // Maps built in a different insertion order (and with a different hasher seed) yield
+        // the same sequence.
+        let mut reversed = names;
+        reversed.reverse();
+        let reversed_info = tx_info(&reversed);
+        assert_eq!(
+            reversed_info
+                .sorted_resources()
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>(),
+            sorted.iter().map(|(name, _)| *name).collect::<Vec<_>>()
+        );
+        for _ in 0..10 {
+            assert_eq!(
+                reversed_info.clone().sorted_resources(),
+                reversed_info.sorted_resources()
+            );
+        }
+    }
+
+    #[test]
+    fn total_l1_gas_test() {
+        let tx_info = TransactionExecutionInfo {
+            actual_resources: HashMap::from([
+                ("l1_gas_usage".to_string(), 1_224),
+                ("n_steps".to_string(), 3_050),
+                ("pedersen_builtin".to_string(), 10),
+                ("range_check_builtin".to_string(), 101),
+            ]),
+            ..Default::default()
+        };
+        let mut block_context = BlockContext::default();
+
+        // The steps are the heaviest Cairo resource: 3050 * 0.01 = 30.5 L1 gas.
+        let compute_gas = (3_050.0 * block_context.cairo_resource_fee_weights["n_steps"]).ceil();
+        assert_eq!(compute_gas, 31.0);
+        assert_eq!(
+            tx_info.total_l1_gas(&block_context),
+            1_224 + compute_gas as u128
+        );
+
+        block_context.starknet_os_config.gas_price = GasPrices::new(3, 0);
+        assert_eq!(
+            calculate_tx_fee(&tx_info.actual_resources, &block_context, &FeeType::Eth).unwrap(),
+            tx_info.total_l1_gas(&block_context) * 3
+        );
+    }
+
+    #[test]
+    fn summary_test() {
+        let mut tx_info = TransactionExecutionInfo {
+            actual_fee: 1234,
+            actual_resources: HashMap::from([
+                ("n_steps".to_string(), 3_000),
+                ("l1_gas_usage".to_string(), 1_224),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            tx_info.summary().to_string(),
+            "fee: 1234, reverted: false, n_steps: 3000"
+        );
+
+        tx_info.revert_error = Some(format!(
+            "Error in the called contract {}\nError at pc=0:12:\nGot an exception",
+            "0x".to_string() + &"ab".repeat(31)
+        ));
+        let summary = tx_info.summary();
+        assert!(summary.reverted);
+        assert_eq!(
+            summary.to_string(),
+            "fee: 1234, reverted: true, n_steps: 3000, revert reason: \
+             Error in the called contract 0xababababababababababababababababa..."
+        );
+
+        tx_info.revert_error = Some("Out of gas".to_string());
+        assert_eq!(
+            tx_info.summary().revert_reason,
+            Some("Out of gas".to_string())
+        );
+    }
+
+    #[test]
+    fn diff_test() {
+        let tx_info = TransactionExecutionInfo {
+            call_info: Some(CallInfo {
+                contract_address: Address(1.into()),
+                retdata: vec![1.into()],
+                events: vec![OrderedEvent::new(1, vec![2.into()], vec![])],
+                internal_calls: vec![CallInfo {
+                    contract_address: Address(2.into()),
+                    events: vec![OrderedEvent::new(0, vec![3.into()], vec![])],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            actual_fee: 100,
+            actual_resources: HashMap::from([
+                ("n_steps".to_string(), 10),
+                ("range_check_builtin".to_string(), 0),
+            ]),
+            ..Default::default()
+        };
+
+        let same_run = TransactionExecutionInfo {
+            actual_resources: HashMap::from([("n_steps".to_string(), 10)]),
+            ..tx_info.clone()
+        };
+        assert_eq!(tx_info.diff(&same_run), vec![]);
+
+        let other_fee = TransactionExecutionInfo {
+            actual_fee: 101,
+            ..tx_info.clone()
+        };
+        assert_eq!(
+            tx_info.diff(&other_fee),
+            vec![ExecInfoDiff::ActualFee(100, 101)]
+        );
+
+        let other_events = TransactionExecutionInfo {
+            call_info: Some(CallInfo {
+                events: vec![],
+                ..tx_info.call_info.clone().unwrap()
+            }),
+            ..tx_info.clone()
+        };
+        assert_eq!(
+            tx_info.diff(&other_events),
+            vec![ExecInfoDiff::Events(
+                "execute",
+                vec![
+                    Event::new(
+                        OrderedEvent::new(0, vec![3.into()], vec![]),
+                        Address(2.into())
+                    ),
+                    Event::new(
+                        OrderedEvent::new(1, vec![2.into()], vec![]),
+                        Address(1.into())
+                    ),
+                ],
+                vec![Event::new(
+                    OrderedEvent::new(0, vec![3.into()], vec![]),
+                    Address(2.into())
+                )],
+            )]
+        );
+    }
+
     #[test]
     fn gen_call_topology_test() {
         // dfs root
@@ -784,6 +1605,35 @@ mod tests {
         )
     }
 
+    #[test]
+    fn iter_calls_test() {
+        let call = |address: u64, internal_calls: Vec<CallInfo>| CallInfo {
+            contract_address: Address(address.into()),
+            internal_calls,
+            ..Default::default()
+        };
+        // A constructor calling two contracts, the first of which makes a nested call.
+        let constructor = call(0, vec![call(1, vec![call(2, vec![])]), call(3, vec![])]);
+        let validate = call(4, vec![call(5, vec![])]);
+        let tx_info = TransactionExecutionInfo {
+            validate_info: Some(validate),
+            call_info: Some(constructor),
+            fee_transfer_info: Some(call(6, vec![])),
+            tx_type: Some(TransactionType::DeployAccount),
+            ..Default::default()
+        };
+
+        assert_eq!(tx_info.iter_calls().count(), 7);
+        assert_eq!(
+            tx_info
+                .iter_calls()
+                .map(|call| call.contract_address.clone())
+                .collect::<Vec<_>>(),
+            (0..7).map(|i| Address(i.into())).collect::<Vec<_>>()
+        );
+        assert_eq!(TransactionExecutionInfo::default().iter_calls().count(), 0);
+    }
+
     #[test]
     fn get_ordered_event_test() {
         // root
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use lazy_static::lazy_static;
 
 use crate::{definitions::transaction_type::TransactionType, transaction::error::TransactionError};
 
@@ -10,6 +11,10 @@ pub(crate) const ESTIMATED_DEPLOY_STEPS: usize = 0;
 pub(crate) const ESTIMATED_DEPLOY_ACCOUNT_STEPS: usize = 3612;
 pub(crate) const ESTIMATED_L1_HANDLER_STEPS: usize = 1068;
 
+lazy_static! {
+    pub(crate) static ref DEFAULT_OS_RESOURCES: OsResources = OsResources::default();
+}
+
 /// Represents the operating system resources associated with syscalls and transactions.
 #[derive(Debug, Clone)]
 pub struct OsResources {
@@ -263,14 +268,38 @@ impl Default for OsResources {
     }
 }
 
+impl OsResources {
+    /// Returns the OS resources of the given transaction type, excluding its syscalls.
+    pub fn tx_resources(&self, tx_type: &TransactionType) -> Option<&ExecutionResources> {
+        self.execute_txs_inner.get(tx_type)
+    }
+
+    /// Replaces the OS resources of the given transaction type, excluding its syscalls.
+    pub fn set_tx_resources(&mut self, tx_type: TransactionType, resources: ExecutionResources) {
+        self.execute_txs_inner.insert(tx_type, resources);
+    }
+
+    /// Replaces the OS resources of each call to the given syscall.
+    pub fn set_syscall_resources(&mut self, syscall: String, resources: ExecutionResources) {
+        self.execute_syscalls.insert(syscall, resources);
+    }
+}
+
 /// Calculate the additional operating system resources required to execute a transaction
 /// given a set of syscalls invoked and a transaction type.
 pub fn get_additional_os_resources(
     syscall_counter: HashMap<String, u64>,
     tx_type: &TransactionType,
 ) -> Result<ExecutionResources, TransactionError> {
-    let os_resources = OsResources::default();
+    get_additional_os_resources_from(&DEFAULT_OS_RESOURCES, syscall_counter, tx_type)
+}
 
+/// Like [get_additional_os_resources], reading the resources from the `os_resources` table.
+pub fn get_additional_os_resources_from(
+    os_resources: &OsResources,
+    syscall_counter: HashMap<String, u64>,
+    tx_type: &TransactionType,
+) -> Result<ExecutionResources, TransactionError> {
     let mut additional_os_resources = ExecutionResources::default();
 
     for (syscall, count) in syscall_counter {
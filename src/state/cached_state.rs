@@ -65,6 +65,21 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
     }
 }
 
+#[cfg(feature = "replay_benchmark")]
+impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
+    /// Returns the storage values read from the state reader, captured even for the
+    /// transactions that reverted.
+    pub fn captured_storage_initial_values(&self) -> &HashMap<StorageEntry, Felt252> {
+        &self.cache.storage_initial_values
+    }
+
+    /// Returns the class hashes read from the state reader, captured even for the transactions
+    /// that reverted.
+    pub fn captured_class_hash_initial_values(&self) -> &HashMap<Address, ClassHash> {
+        &self.cache.class_hash_initial_values
+    }
+}
+
 impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
     /// Constructor, creates a new cached state.
     pub fn new(state_reader: Arc<T>, contract_classes: Arc<C>) -> Self {
@@ -125,6 +140,23 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
             .into_iter())
     }
 
+    /// Returns the contract class for a given class hash as of the given block number.
+    /// The shared contract class cache is queried first so that versioned caches can provide the
+    /// class that was valid at that block. Otherwise it falls back to `get_contract_class`.
+    pub fn get_contract_class_at(
+        &mut self,
+        class_hash: &ClassHash,
+        block_number: u64,
+    ) -> Result<CompiledClass, StateError> {
+        match self
+            .contract_class_cache
+            .get_contract_class_at(*class_hash, block_number)
+        {
+            Some(compiled_class) => Ok(compiled_class),
+            None => State::get_contract_class(self, class_hash),
+        }
+    }
+
     /// Creates a copy of this state with an empty cache for saving changes and applying them
     /// later.
     pub fn create_transactional(&self) -> Result<CachedState<T, C>, StateError> {
@@ -6,9 +6,15 @@
 //! The trait `ContractClassCache` provides methods for retrieving and inserting elements into the
 //! cache. It also contains a method to extend the shared cache from an iterator so that it can be
 //! used with the private caches.
+//!
+//! Caches may also be aware of the block number from which a compiled class is valid, which is
+//! needed when replaying old blocks whose classes were compiled with a different compiler version.
 
 use crate::{services::api::contract_classes::compiled_class::CompiledClass, utils::ClassHash};
-use std::{collections::HashMap, sync::RwLock};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::RwLock,
+};
 
 /// The contract class cache trait, which must be implemented by all caches.
 pub trait ContractClassCache {
@@ -17,6 +23,16 @@ pub trait ContractClassCache {
     fn get_contract_class(&self, class_hash: ClassHash) -> Option<CompiledClass>;
     /// Inserts or replaces a contract class associated with a specific class hash.
     fn set_contract_class(&self, class_hash: ClassHash, compiled_class: CompiledClass);
+    /// Provides the contract class associated with a specific class hash as of a given block
+    /// number, or `None` if not present. Caches that don't track class versions ignore the block
+    /// number.
+    fn get_contract_class_at(
+        &self,
+        class_hash: ClassHash,
+        _block_number: u64,
+    ) -> Option<CompiledClass> {
+        self.get_contract_class(class_hash)
+    }
 }
 
 /// A contract class cache which stores nothing. In other words, using this as a cache means there's
@@ -88,3 +104,85 @@ impl IntoIterator for &PermanentContractClassCache {
         self.storage.read().unwrap().clone().into_iter()
     }
 }
+
+/// A contract class cache which stores every version of a contract class along with the block
+/// number from which each version is valid. Lookups without a block number return the latest
+/// version.
+#[derive(Debug, Default)]
+pub struct VersionedContractClassCache {
+    storage: RwLock<HashMap<ClassHash, BTreeMap<u64, CompiledClass>>>,
+}
+
+impl VersionedContractClassCache {
+    /// Inserts or replaces the version of a contract class valid from `block_number` onwards.
+    pub fn set_contract_class_at(
+        &self,
+        class_hash: ClassHash,
+        block_number: u64,
+        compiled_class: CompiledClass,
+    ) {
+        self.storage
+            .write()
+            .unwrap()
+            .entry(class_hash)
+            .or_default()
+            .insert(block_number, compiled_class);
+    }
+}
+
+impl ContractClassCache for VersionedContractClassCache {
+    fn get_contract_class(&self, class_hash: ClassHash) -> Option<CompiledClass> {
+        self.storage
+            .read()
+            .unwrap()
+            .get(&class_hash)
+            .and_then(|versions| versions.values().next_back().cloned())
+    }
+
+    fn set_contract_class(&self, class_hash: ClassHash, compiled_class: CompiledClass) {
+        self.set_contract_class_at(class_hash, 0, compiled_class);
+    }
+
+    fn get_contract_class_at(
+        &self,
+        class_hash: ClassHash,
+        block_number: u64,
+    ) -> Option<CompiledClass> {
+        self.storage
+            .read()
+            .unwrap()
+            .get(&class_hash)
+            .and_then(|versions| versions.range(..=block_number).next_back())
+            .map(|(_, compiled_class)| compiled_class.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::api::contract_classes::deprecated_contract_class::ContractClass;
+    use std::sync::Arc;
+
+    #[test]
+    fn versioned_cache_resolves_class_by_block_number() {
+        let class_hash = ClassHash([1; 32]);
+        let old_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/account_without_validation.json").unwrap(),
+        ));
+        let new_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/constructor.json").unwrap(),
+        ));
+
+        let cache = VersionedContractClassCache::default();
+        cache.set_contract_class_at(class_hash, 0, old_class.clone());
+        cache.set_contract_class_at(class_hash, 10, new_class.clone());
+
+        assert_eq!(cache.get_contract_class_at(class_hash, 5), Some(old_class));
+        assert_eq!(
+            cache.get_contract_class_at(class_hash, 10),
+            Some(new_class.clone())
+        );
+        assert_eq!(cache.get_contract_class(class_hash), Some(new_class));
+        assert_eq!(cache.get_contract_class_at(ClassHash([2; 32]), 5), None);
+    }
+}
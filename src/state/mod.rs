@@ -5,6 +5,7 @@ use self::{
 use crate::{
     core::errors::state_errors::StateError,
     definitions::block_context::GasPrices,
+    execution::Syscall,
     transaction::error::TransactionError,
     utils::{
         get_keys, to_cache_state_storage_mapping, to_state_diff_storage_mapping, Address,
@@ -113,6 +114,30 @@ impl ExecutionResourcesManager {
             .get(syscall_name)
             .map(ToOwned::to_owned)
     }
+
+    /// Returns the gas the counted syscalls cost under the syscall gas schedule, by syscall.
+    /// Syscalls the schedule doesn't price, such as the Cairo 0 getters, are left out.
+    pub fn syscall_gas(&self) -> HashMap<Syscall, u128> {
+        self.syscall_counter
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .filter_map(|(syscall_name, count)| {
+                let syscall = Syscall::from_name(syscall_name)?;
+                Some((syscall, u128::from(*count) * syscall.gas_cost()))
+            })
+            .collect()
+    }
+}
+
+/// The updates a state diff makes to the leaf of a contract in the state commitment tree.
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct ContractLeafUpdate {
+    /// New class hash of the contract, if it changed.
+    pub class_hash: Option<ClassHash>,
+    /// New nonce of the contract, if it changed.
+    pub nonce: Option<Felt252>,
+    /// New values of the contract's storage tree leaves.
+    pub storage_updates: HashMap<Felt252, Felt252>,
 }
 
 /// Represents a difference in state between two points in time.
@@ -224,6 +249,27 @@ impl StateDiff {
             storage_updates,
         }
     }
+
+    /// Groups the contract updates of the diff by address, as the leaf updates that would feed
+    /// the state commitment. Compiled class hash updates belong to the classes tree and aren't
+    /// included.
+    pub fn contract_leaf_updates(&self) -> HashMap<Address, ContractLeafUpdate> {
+        let mut leaf_updates: HashMap<Address, ContractLeafUpdate> = HashMap::new();
+        for (address, class_hash) in &self.address_to_class_hash {
+            leaf_updates.entry(address.clone()).or_default().class_hash = Some(*class_hash);
+        }
+        for (address, nonce) in &self.address_to_nonce {
+            leaf_updates.entry(address.clone()).or_default().nonce = Some(*nonce);
+        }
+        for (address, storage_updates) in &self.storage_updates {
+            leaf_updates
+                .entry(address.clone())
+                .or_default()
+                .storage_updates
+                .extend(storage_updates.clone());
+        }
+        leaf_updates
+    }
 }
 
 /// Validates that block progression from a default block to the next one is legal.
@@ -15,7 +15,9 @@ use crate::{
     core::errors::state_errors::StateError,
     definitions::{
         block_context::BlockContext,
-        constants::{BLOCK_HASH_CONTRACT_ADDRESS, CONSTRUCTOR_ENTRY_POINT_SELECTOR},
+        constants::{
+            BLOCK_HASH_CONTRACT_ADDRESS, CONSTRUCTOR_ENTRY_POINT_SELECTOR, L1_GAS, L2_GAS,
+        },
     },
     execution::{
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
@@ -36,7 +38,7 @@ use crate::{
         state_api::{State, StateReader},
         BlockInfo, ExecutionResourcesManager,
     },
-    transaction::error::TransactionError,
+    transaction::{error::TransactionError, VersionSpecificAccountTxFields},
     utils::{calculate_sn_keccak, felt_to_hash, get_big_int, get_felt_range, Address, ClassHash},
 };
 use cairo_vm::Felt252;
@@ -747,6 +749,62 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         let signature_start_ptr = self.allocate_segment(vm, signature)?;
         let signature_end_ptr = (signature_start_ptr + self.tx_execution_context.signature.len())?;
 
+        // Allocate the V3 fields, which are left empty for older transaction versions
+        let current_fields = match &self.tx_execution_context.account_tx_fields {
+            VersionSpecificAccountTxFields::Current(current) => Some(current.clone()),
+            VersionSpecificAccountTxFields::Deprecated(_) => None,
+        };
+        let resource_bounds: Vec<MaybeRelocatable> = current_fields
+            .iter()
+            .flat_map(|current| {
+                [
+                    (*L1_GAS, &current.resource_bounds.l1_gas),
+                    (*L2_GAS, &current.resource_bounds.l2_gas),
+                ]
+            })
+            .flat_map(|(resource, bounds)| {
+                let (max_amount, max_price_per_unit) = bounds
+                    .as_ref()
+                    .map(|bounds| (bounds.max_amount, bounds.max_price_per_unit))
+                    .unwrap_or_default();
+                [
+                    MaybeRelocatable::from(resource),
+                    MaybeRelocatable::from(Felt252::from(max_amount)),
+                    MaybeRelocatable::from(Felt252::from(max_price_per_unit)),
+                ]
+            })
+            .collect();
+        let (resource_bounds_start_ptr, resource_bounds_end_ptr) =
+            self.allocate_span(vm, resource_bounds)?;
+        let (paymaster_data_start_ptr, paymaster_data_end_ptr) = self.allocate_span(
+            vm,
+            current_fields
+                .iter()
+                .flat_map(|current| current.paymaster_data.iter().map(MaybeRelocatable::from))
+                .collect(),
+        )?;
+        let (account_deployment_data_start_ptr, account_deployment_data_end_ptr) = self
+            .allocate_span(
+                vm,
+                current_fields
+                    .iter()
+                    .flat_map(|current| {
+                        current
+                            .account_deployment_data
+                            .iter()
+                            .map(MaybeRelocatable::from)
+                    })
+                    .collect(),
+            )?;
+        let tip = current_fields.as_ref().map_or(0, |current| current.tip);
+        let nonce_data_availability_mode =
+            current_fields.as_ref().map_or(Felt252::ZERO, |current| {
+                Felt252::from(&current.nonce_data_availability_mode)
+            });
+        let fee_data_availability_mode = current_fields.as_ref().map_or(Felt252::ZERO, |current| {
+            Felt252::from(&current.fee_data_availability_mode)
+        });
+
         // Allocate tx info
         let tx_info = &self.tx_execution_context;
         let tx_info_data = vec![
@@ -758,6 +816,15 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             MaybeRelocatable::from(&tx_info.transaction_hash),
             MaybeRelocatable::from(&self.block_context.starknet_os_config.chain_id),
             MaybeRelocatable::from(&tx_info.nonce),
+            resource_bounds_start_ptr.into(),
+            resource_bounds_end_ptr.into(),
+            MaybeRelocatable::from(Felt252::from(tip)),
+            paymaster_data_start_ptr.into(),
+            paymaster_data_end_ptr.into(),
+            MaybeRelocatable::from(nonce_data_availability_mode),
+            MaybeRelocatable::from(fee_data_availability_mode),
+            account_deployment_data_start_ptr.into(),
+            account_deployment_data_end_ptr.into(),
         ];
         let tx_info_ptr = self.allocate_segment(vm, tx_info_data)?;
 
@@ -1003,6 +1070,18 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         Ok(segment_start)
     }
 
+    /// Allocates a read-only segment with `data` and returns the start and end pointers of the
+    /// resulting span.
+    fn allocate_span(
+        &mut self,
+        vm: &mut VirtualMachine,
+        data: Vec<MaybeRelocatable>,
+    ) -> Result<(Relocatable, Relocatable), SyscallHandlerError> {
+        let len = data.len();
+        let start_ptr = self.allocate_segment(vm, data)?;
+        Ok((start_ptr, (start_ptr + len)?))
+    }
+
     /// Sends a message from L2 to L1, including the destination address and payload.
     fn send_message_to_l1(
         &mut self,
@@ -1171,3 +1250,147 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BusinessLogicSyscallHandler;
+    use crate::{
+        definitions::{
+            block_context::BlockContext,
+            constants::{L1_GAS, L2_GAS},
+        },
+        execution::TransactionExecutionContext,
+        state::{
+            cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader, ExecutionResourcesManager,
+        },
+        transaction::{
+            CurrentAccountTxFields, DataAvailabilityMode, ResourceBounds, ResourceBoundsMapping,
+            VersionSpecificAccountTxFields,
+        },
+        utils::{get_big_int, get_felt_range, get_relocatable, Address},
+    };
+    use cairo_vm::{types::relocatable::Relocatable, vm::vm_core::VirtualMachine, Felt252};
+
+    /// Writes the execution info for a transaction with the given fields and returns the VM
+    /// holding it together with the pointer to its tx info.
+    fn allocate_tx_info(
+        account_tx_fields: VersionSpecificAccountTxFields,
+    ) -> (VirtualMachine, Relocatable) {
+        let mut state = CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
+        let mut syscall_handler = BusinessLogicSyscallHandler::new(
+            TransactionExecutionContext::new(
+                Address(1.into()),
+                2.into(),
+                vec![3.into()],
+                account_tx_fields,
+                4.into(),
+                0,
+                1.into(),
+            ),
+            &mut state,
+            ExecutionResourcesManager::default(),
+            Address(Felt252::ZERO),
+            Address(1.into()),
+            BlockContext::default(),
+            Relocatable::from((0, 0)),
+            false,
+            Felt252::ZERO,
+        );
+        let mut vm = VirtualMachine::new(false);
+
+        let execution_info_ptr = syscall_handler
+            .get_or_allocate_execution_info(&mut vm)
+            .unwrap();
+        let tx_info_ptr = get_relocatable(&vm, (execution_info_ptr + 1).unwrap()).unwrap();
+        (vm, tx_info_ptr)
+    }
+
+    fn get_span(vm: &VirtualMachine, tx_info_ptr: Relocatable, offset: usize) -> Vec<Felt252> {
+        get_felt_range(
+            vm,
+            get_relocatable(vm, (tx_info_ptr + offset).unwrap()).unwrap(),
+            get_relocatable(vm, (tx_info_ptr + offset + 1).unwrap()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn tx_info_exposes_current_account_tx_fields() {
+        let (vm, tx_info_ptr) = allocate_tx_info(VersionSpecificAccountTxFields::Current(
+            CurrentAccountTxFields {
+                resource_bounds: ResourceBoundsMapping {
+                    l1_gas: Some(ResourceBounds {
+                        max_amount: 10,
+                        max_price_per_unit: 20,
+                    }),
+                    l2_gas: None,
+                },
+                tip: 7,
+                nonce_data_availability_mode: DataAvailabilityMode::L2,
+                fee_data_availability_mode: DataAvailabilityMode::L1,
+                paymaster_data: vec![5.into()],
+                account_deployment_data: vec![6.into()],
+            },
+        ));
+
+        assert_eq!(
+            get_span(&vm, tx_info_ptr, 8),
+            vec![
+                *L1_GAS,
+                10.into(),
+                20.into(),
+                *L2_GAS,
+                Felt252::ZERO,
+                Felt252::ZERO
+            ]
+        );
+        assert_eq!(
+            get_big_int(&vm, (tx_info_ptr + 10).unwrap()).unwrap(),
+            7.into()
+        );
+        assert_eq!(get_span(&vm, tx_info_ptr, 11), vec![5.into()]);
+        assert_eq!(
+            get_big_int(&vm, (tx_info_ptr + 13).unwrap()).unwrap(),
+            Felt252::ONE
+        );
+        assert_eq!(
+            get_big_int(&vm, (tx_info_ptr + 14).unwrap()).unwrap(),
+            Felt252::ZERO
+        );
+        assert_eq!(get_span(&vm, tx_info_ptr, 15), vec![6.into()]);
+    }
+
+    /// Invoke and Declare transactions carry deprecated fields, so they must keep the leading
+    /// tx info layout and get empty spans and zeros for the current fields.
+    #[test]
+    fn tx_info_leaves_current_fields_empty_for_deprecated_fields() {
+        let (vm, tx_info_ptr) =
+            allocate_tx_info(VersionSpecificAccountTxFields::new_deprecated(100));
+
+        assert_eq!(
+            get_felt_range(&vm, tx_info_ptr, (tx_info_ptr + 3).unwrap()).unwrap(),
+            vec![Felt252::ONE, Felt252::ONE, 100.into()]
+        );
+        assert_eq!(get_span(&vm, tx_info_ptr, 3), vec![3.into()]);
+        assert_eq!(
+            get_big_int(&vm, (tx_info_ptr + 7).unwrap()).unwrap(),
+            4.into()
+        );
+        assert!(get_span(&vm, tx_info_ptr, 8).is_empty());
+        assert_eq!(
+            get_big_int(&vm, (tx_info_ptr + 10).unwrap()).unwrap(),
+            Felt252::ZERO
+        );
+        assert!(get_span(&vm, tx_info_ptr, 11).is_empty());
+        assert_eq!(
+            get_big_int(&vm, (tx_info_ptr + 13).unwrap()).unwrap(),
+            Felt252::ZERO
+        );
+        assert_eq!(
+            get_big_int(&vm, (tx_info_ptr + 14).unwrap()).unwrap(),
+            Felt252::ZERO
+        );
+        assert!(get_span(&vm, tx_info_ptr, 15).is_empty());
+    }
+}
@@ -28,7 +28,7 @@ use crate::{
 use cairo_vm::Felt252;
 use num_traits::Zero;
 
-use super::fee::{calculate_tx_fee, charge_fee};
+use super::fee::{calculate_tx_fee, charge_fee, FeeRounding};
 use super::{get_tx_version, Transaction};
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -411,6 +411,8 @@ impl Declare {
             self.max_fee,
             &mut tx_execution_context,
             self.skip_fee_transfer,
+            &FeeRounding::default(),
+            &FeeType::Eth,
             #[cfg(feature = "cairo-native")]
             program_cache,
         )?;
@@ -579,6 +581,15 @@ mod tests {
             actual_fee: 0,
             actual_resources,
             tx_type: Some(TransactionType::Declare),
+            fee_overage: None,
+            sequencer_balance_delta: None,
+            commitment_leaf_updates: None,
+            storage_access_counts: None,
+            fee_paid_in: None,
+            post_constructor_state_diff: None,
+            syscall_gas: None,
+            #[cfg(feature = "timings")]
+            phase_timings: Default::default(),
         };
 
         // ---------------------
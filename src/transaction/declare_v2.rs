@@ -1,4 +1,4 @@
-use super::fee::{calculate_tx_fee, charge_fee};
+use super::fee::{calculate_tx_fee, charge_fee, FeeRounding};
 use super::{
     check_account_tx_fields_version, get_tx_version, ResourceBounds, Transaction,
     VersionSpecificAccountTxFields,
@@ -427,6 +427,8 @@ impl DeclareV2 {
             self.account_tx_fields.max_fee(),
             &mut tx_execution_context,
             self.skip_fee_transfer,
+            &FeeRounding::default(),
+            &FeeType::Eth,
             #[cfg(feature = "cairo-native")]
             program_cache,
         )?;
@@ -584,7 +586,7 @@ impl DeclareV2 {
             account_tx_fields: if ignore_max_fee {
                 if let VersionSpecificAccountTxFields::Current(current) = &self.account_tx_fields {
                     let mut current_fields = current.clone();
-                    current_fields.l1_resource_bounds = Some(ResourceBounds {
+                    current_fields.resource_bounds.l1_gas = Some(ResourceBounds {
                         max_amount: u64::MAX,
                         max_price_per_unit: u128::MAX,
                     });
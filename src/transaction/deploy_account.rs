@@ -1,6 +1,7 @@
-use super::fee::{calculate_tx_fee, charge_fee};
+use super::fee::{charge_fee, FeeEstimate, FeeRounding, FeeStrategy};
 use super::{
-    check_account_tx_fields_version, get_tx_version, ResourceBounds, VersionSpecificAccountTxFields,
+    check_account_tx_fields_version, get_tx_version, ResourceBounds, ResourceBoundsMapping,
+    SkipFlags, VersionSpecificAccountTxFields,
 };
 use super::{invoke_function::verify_no_calls_to_other_contracts, Transaction};
 use crate::definitions::block_context::FeeType;
@@ -10,8 +11,9 @@ use crate::execution::gas_usage::get_onchain_data_segment_length;
 use crate::execution::os_usage::ESTIMATED_DEPLOY_ACCOUNT_STEPS;
 use crate::services::api::contract_classes::deprecated_contract_class::EntryPointType;
 use crate::services::eth_definitions::eth_gas_constants::SHARP_GAS_PER_MEMORY_WORD;
-use crate::state::cached_state::CachedState;
+use crate::state::cached_state::{CachedState, UNINITIALIZED_CLASS_HASH};
 use crate::state::state_api::StateChangesCount;
+use crate::state::state_cache::{StateCache, StorageEntry};
 use crate::state::StateDiff;
 use crate::{
     core::{
@@ -19,18 +21,18 @@ use crate::{
         transaction_hash::calculate_deploy_account_transaction_hash,
     },
     definitions::{
-        block_context::BlockContext,
+        block_context::{BlockContext, GasPrices},
         constants::{
-            CONSTRUCTOR_ENTRY_POINT_SELECTOR, INITIAL_GAS_COST,
+            CONSTRUCTOR_ENTRY_POINT_SELECTOR, DEFAULT_ENTRY_POINT_SELECTOR, INITIAL_GAS_COST,
             VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR,
         },
         transaction_type::TransactionType,
     },
     execution::{
-        execution_entry_point::ExecutionEntryPoint, CallInfo, TransactionExecutionContext,
-        TransactionExecutionInfo,
+        execution_entry_point::ExecutionEntryPoint, Builtin, CallInfo, CallType,
+        StorageAccessCounts, TransactionExecutionContext, TransactionExecutionInfo,
     },
-    hash_utils::calculate_contract_address,
+    hash_utils::{calculate_contract_address, compute_hash_on_elements},
     services::api::{
         contract_class_errors::ContractClassError, contract_classes::compiled_class::CompiledClass,
     },
@@ -40,13 +42,20 @@ use crate::{
         ExecutionResourcesManager,
     },
     transaction::error::TransactionError,
-    utils::{calculate_tx_resources, Address, ClassHash},
+    utils::{
+        calculate_tx_resources_with_os_resources, get_uint256_storage_var_addresses, Address,
+        ClassHash,
+    },
 };
-use cairo_vm::Felt252;
-use getset::Getters;
-use num_traits::Zero;
+use cairo_vm::{utils::felt_to_biguint, Felt252};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use num_traits::{ToPrimitive, Zero};
+use starknet_crypto::{verify, FieldElement};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::mem::{discriminant, Discriminant};
+use std::time::Instant;
 
 #[cfg(feature = "cairo-native")]
 use {
@@ -54,6 +63,9 @@ use {
     std::{cell::RefCell, rc::Rc},
 };
 
+#[cfg(feature = "timings")]
+use crate::execution::PhaseTimings;
+
 /// Struct representing the state selector, containing contract addresses and class hashes.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StateSelector {
@@ -61,8 +73,253 @@ pub struct StateSelector {
     pub class_hashes: Vec<ClassHash>,
 }
 
+/// What must be present in the state for a deploy account transaction to go through.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preconditions {
+    /// The account class, which must be declared.
+    pub class_hash: ClassHash,
+    /// The token the fee is paid with.
+    pub fee_token_address: Address,
+    /// The minimal balance of the account in the fee token.
+    pub min_balance: u128,
+}
+
+/// A state write performed while deploying an account, reported to the callback given to
+/// [DeployAccount::execute_with_state_write_callback].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateWriteEvent {
+    /// The account class was assigned to the account address.
+    Deploy {
+        contract_address: Address,
+        class_hash: ClassHash,
+    },
+    /// A storage slot was written.
+    StorageWrite {
+        contract_address: Address,
+        key: [u8; 32],
+        value: Felt252,
+    },
+    /// A contract nonce was updated.
+    NonceUpdate {
+        contract_address: Address,
+        nonce: Felt252,
+    },
+}
+
+/// Estimated fees of the same deploy account under the V1 and the V3 fee regimes, as returned
+/// by [DeployAccount::fee_comparison]. Each fee is denominated in the token of its regime.
+#[derive(Clone, Debug)]
+pub struct FeeComparison {
+    /// Fee paid in ETH (wei) under the V1 regime.
+    pub v1_fee: u128,
+    /// Fee paid in STRK (fri) under the V3 regime.
+    pub v3_fee: u128,
+    /// L1 gas bounds of a V3 variant of the transaction, derived from the V3 fee at the block's
+    /// STRK gas price.
+    pub v3_l1_resource_bounds: ResourceBounds,
+}
+
+/// How [DeployAccount::declare_classes] handles a class that is already in the state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateClassPolicy {
+    /// The class in the state is kept and the declaration is a no-op.
+    #[default]
+    Skip,
+    /// The class in the state is replaced.
+    Replace,
+    /// The declaration fails with [TransactionError::ClassAlreadyDeclared].
+    Reject,
+}
+
+/// The inputs of the contract address derivation of a deploy account transaction, as hashed
+/// by [calculate_contract_address], to compare step by step with another implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressDerivation {
+    pub salt: Felt252,
+    pub class_hash: Felt252,
+    /// Pedersen hash of the constructor calldata.
+    pub constructor_calldata_hash: Felt252,
+    /// Always zero, as accounts deploy themselves.
+    pub deployer_address: Address,
+    /// The address derived from the inputs above.
+    pub contract_address: Address,
+}
+
+/// Outcome counts of simulating a batch of deploy account transactions, with the failures
+/// grouped by [TransactionError] variant.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimulationReport {
+    pub succeeded: usize,
+    pub failures_by_variant: HashMap<Discriminant<TransactionError>, usize>,
+}
+
+impl SimulationReport {
+    /// Records the outcome of a transaction.
+    pub fn record<T>(&mut self, result: &Result<T, TransactionError>) {
+        match result {
+            Ok(_) => self.succeeded += 1,
+            Err(error) => {
+                *self
+                    .failures_by_variant
+                    .entry(discriminant(error))
+                    .or_default() += 1
+            }
+        }
+    }
+
+    /// Returns how many transactions failed with the same variant as `error`.
+    pub fn failures_like(&self, error: &TransactionError) -> usize {
+        self.failures_by_variant
+            .get(&discriminant(error))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns how many transactions failed.
+    pub fn failed(&self) -> usize {
+        self.failures_by_variant.values().sum()
+    }
+}
+
+/// The raw fields of a deploy account transaction, before its hash is computed.
+/// The contract address can be computed without the transaction hash, which allows discarding
+/// candidates (e.g. duplicates) before paying for the hash in [DeployAccountUnhashed::finalize].
+#[derive(Clone, Debug)]
+pub struct DeployAccountUnhashed {
+    pub class_hash: ClassHash,
+    pub account_tx_fields: VersionSpecificAccountTxFields,
+    pub version: Felt252,
+    pub nonce: Felt252,
+    pub constructor_calldata: Vec<Felt252>,
+    pub signature: Vec<Felt252>,
+    pub contract_address_salt: Felt252,
+}
+
+impl DeployAccountUnhashed {
+    /// Computes the address the account will be deployed at.
+    pub fn contract_address(&self) -> Result<Address, TransactionError> {
+        Ok(Address(calculate_contract_address(
+            &self.contract_address_salt,
+            &Felt252::from_bytes_be(&self.class_hash.0),
+            &self.constructor_calldata,
+            Address(Felt252::ZERO),
+        )?))
+    }
+
+    /// Computes the transaction hash for `chain_id` and builds the [DeployAccount].
+    pub fn finalize(self, chain_id: Felt252) -> Result<DeployAccount, TransactionError> {
+        DeployAccount::new(
+            self.class_hash,
+            self.account_tx_fields,
+            self.version,
+            self.nonce,
+            self.constructor_calldata,
+            self.signature,
+            self.contract_address_salt,
+            chain_id,
+        )
+    }
+}
+
+/// Execution policies of a [DeployAccount] that aren't part of the transaction data, such as
+/// replay, simulation and reporting options. The defaults execute the transaction as the
+/// network does.
+#[derive(Clone, Debug, Default, Getters, CopyGetters, Setters)]
+pub struct DeployAccountOptions {
+    /// When replaying, the block number used to resolve the account class from a versioned
+    /// contract class cache.
+    #[getset(get_copy = "pub", set = "pub")]
+    replay_block_number: Option<u64>,
+    /// When set, a calculated fee above the max fee doesn't revert the transaction; the
+    /// execution result is kept and the overage is reported instead.
+    #[getset(get_copy = "pub", set = "pub")]
+    report_fee_overage_without_revert: bool,
+    /// When set, the constructor is executed with this caller address instead of zero.
+    /// Constructors have no caller per the spec, so this is only meant for testing
+    /// constructors that branch on their caller.
+    #[getset(get = "pub", set = "pub")]
+    constructor_caller_override: Option<Address>,
+    /// When set, the compiled class hash stored for `class_hash` must match it for the deploy
+    /// to go through.
+    #[getset(get_copy = "pub", set = "pub")]
+    expected_compiled_class_hash: Option<Felt252>,
+    /// Rounding applied to the L1 gas usage when computing this transaction's fees.
+    #[getset(get_copy = "pub", set = "pub")]
+    fee_rounding: FeeRounding,
+    /// How far ahead of the account's current nonce the transaction nonce may be, so that
+    /// queued transactions can be validated ahead of time. Zero requires an exact match.
+    #[getset(get_copy = "pub", set = "pub")]
+    max_nonce_gap: u64,
+    /// When set, the fee is paid with this token instead of the one implied by the account tx
+    /// fields.
+    #[getset(get_copy = "pub")]
+    fee_type_override: Option<FeeType>,
+    /// When set, the sequencer's fee token balance is read before and after charging the fee,
+    /// and the difference is reported as
+    /// [TransactionExecutionInfo::sequencer_balance_delta].
+    #[getset(get_copy = "pub", set = "pub")]
+    record_sequencer_balance_delta: bool,
+    /// When set, the leaf updates of the state commitment caused by the applied state diff are
+    /// reported as [TransactionExecutionInfo::commitment_leaf_updates]. The fee transfer isn't
+    /// part of that diff.
+    #[getset(get_copy = "pub", set = "pub")]
+    record_commitment_leaf_updates: bool,
+    /// When set, the storage reads and writes performed by the validation and the constructor
+    /// are counted and reported as [TransactionExecutionInfo::storage_access_counts].
+    #[getset(get_copy = "pub", set = "pub")]
+    record_storage_access_counts: bool,
+    /// When set, a snapshot of the state diff between the constructor and the validation is
+    /// taken and reported as [TransactionExecutionInfo::post_constructor_state_diff], e.g. to
+    /// step-debug what the validation sees.
+    #[getset(get_copy = "pub", set = "pub")]
+    record_post_constructor_state_diff: bool,
+    /// When set, the gas the syscalls of the validation and the constructor cost is reported by
+    /// syscall as [TransactionExecutionInfo::syscall_gas]. Cairo 0 classes aren't charged gas,
+    /// so their syscalls are priced as the Cairo 1 ones.
+    #[getset(get_copy = "pub", set = "pub")]
+    record_syscall_gas: bool,
+    /// When set, `__validate_deploy__` isn't executed and this result is used in its place,
+    /// which allows simulating accounts whose signature verification can't be satisfied.
+    #[getset(get = "pub", set = "pub")]
+    validate_result_override: Option<CallInfo>,
+    /// When set, the `(validate, execute)` step limits used instead of the block context's.
+    /// The constructor and the fee transfer run under the execute limit.
+    #[getset(get_copy = "pub", set = "pub")]
+    max_n_steps_override: Option<(u64, u64)>,
+    /// When set, the minimal fee checked before execution expects a class with a constructor
+    /// to initialize a storage slot, on top of the fee balance update of every deploy.
+    #[getset(get_copy = "pub", set = "pub")]
+    constructor_aware_fee_estimate: bool,
+    /// When set, this class is executed instead of the one the state holds for `class_hash`,
+    /// e.g. to replay a historical deploy with a locally patched class. The state's class is
+    /// left untouched.
+    #[getset(get = "pub", set = "pub")]
+    class_override: Option<CompiledClass>,
+    /// Storage values, keyed by contract address and storage key, read in place of the state's
+    /// during the execution, e.g. to test constructors under hypothetical storage states. They
+    /// aren't persisted, unless the transaction itself writes them.
+    #[getset(get = "pub", set = "pub")]
+    storage_overlay: HashMap<(Address, Felt252), Felt252>,
+    /// When set, the L1 gas prices the transaction was originally executed under, used to
+    /// compute its fees instead of the block context's, e.g. when replaying historical blocks.
+    #[getset(get = "pub", set = "pub")]
+    recorded_gas_prices: Option<GasPrices>,
+    /// Simulation only: when set, the contract isn't deployed and its constructor isn't run.
+    /// The class is bound to the contract address on a throwaway copy of the state just for
+    /// the validation, e.g. to check the signature logic of a class. This doesn't match what
+    /// the network executes: the validation sees no constructor-initialized storage, and its
+    /// writes are discarded along with the binding.
+    #[getset(get_copy = "pub", set = "pub")]
+    validate_without_deploy: bool,
+    /// When set, the execution fails with [TransactionError::Timeout] once this instant is
+    /// reached. The deadline is checked between the execution phases: a phase that has started,
+    /// such as the constructor, runs to completion (within its step limit) before the check.
+    #[getset(get_copy = "pub", set = "pub")]
+    deadline: Option<Instant>,
+}
+
 /// Struct representing a type of transaction: deploy account.
-#[derive(Clone, Debug, Getters)]
+#[derive(Clone, Debug, Getters, CopyGetters, MutGetters, Setters)]
 pub struct DeployAccount {
     #[getset(get = "pub")]
     contract_address: Address,
@@ -83,11 +340,21 @@ pub struct DeployAccount {
     skip_execute: bool,
     skip_fee_transfer: bool,
     skip_nonce_check: bool,
+    /// The chain id the transaction hash was computed for. `None` when the hash was provided
+    /// by the caller, in which case the chain isn't checked on execution.
+    #[getset(get_copy = "pub")]
+    chain_id: Option<Felt252>,
+    /// How the transaction is executed, beyond what the transaction data specifies.
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    options: DeployAccountOptions,
 }
 
 impl DeployAccount {
     #[allow(clippy::too_many_arguments)]
     /// Constructor create a new DeployAccount.
+    /// `chain_id` can be any felt, such as the id of an app-chain, not only one of
+    /// [StarknetChainId](crate::definitions::block_context::StarknetChainId). It must match the
+    /// chain id of the block context the transaction is executed in.
     pub fn new(
         class_hash: ClassHash,
         account_tx_fields: VersionSpecificAccountTxFields,
@@ -98,14 +365,117 @@ impl DeployAccount {
         contract_address_salt: Felt252,
         chain_id: Felt252,
     ) -> Result<Self, TransactionError> {
+        Self::new_with_address_validation(
+            class_hash,
+            account_tx_fields,
+            version,
+            nonce,
+            constructor_calldata,
+            signature,
+            contract_address_salt,
+            chain_id,
+            |_, _| Ok(()),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Creates a new DeployAccount, running `validate_address` on the computed contract address
+    /// and the class hash before the transaction hash is calculated.
+    /// This allows callers to enforce their deployment policies at construction time.
+    pub fn new_with_address_validation<F>(
+        class_hash: ClassHash,
+        account_tx_fields: VersionSpecificAccountTxFields,
+        version: Felt252,
+        nonce: Felt252,
+        constructor_calldata: Vec<Felt252>,
+        signature: Vec<Felt252>,
+        contract_address_salt: Felt252,
+        chain_id: Felt252,
+        validate_address: F,
+    ) -> Result<Self, TransactionError>
+    where
+        F: FnOnce(&Address, &ClassHash) -> Result<(), TransactionError>,
+    {
+        Self::new_with_address_derivation_and_validation(
+            class_hash,
+            account_tx_fields,
+            version,
+            nonce,
+            constructor_calldata,
+            signature,
+            contract_address_salt,
+            chain_id,
+            |salt, class_hash, constructor_calldata, deployer_address| {
+                Ok(calculate_contract_address(
+                    salt,
+                    class_hash,
+                    constructor_calldata,
+                    deployer_address.clone(),
+                )?)
+            },
+            validate_address,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Creates a new DeployAccount whose contract address is derived by `derive_address` instead
+    /// of the standard scheme, e.g. to experiment with other address schemes.
+    /// `derive_address` is given the salt, the class hash, the constructor calldata and the
+    /// deployer address, which is zero for deploy account transactions.
+    pub fn new_with_address_derivation<D>(
+        class_hash: ClassHash,
+        account_tx_fields: VersionSpecificAccountTxFields,
+        version: Felt252,
+        nonce: Felt252,
+        constructor_calldata: Vec<Felt252>,
+        signature: Vec<Felt252>,
+        contract_address_salt: Felt252,
+        chain_id: Felt252,
+        derive_address: D,
+    ) -> Result<Self, TransactionError>
+    where
+        D: FnOnce(&Felt252, &Felt252, &[Felt252], &Address) -> Result<Felt252, TransactionError>,
+    {
+        Self::new_with_address_derivation_and_validation(
+            class_hash,
+            account_tx_fields,
+            version,
+            nonce,
+            constructor_calldata,
+            signature,
+            contract_address_salt,
+            chain_id,
+            derive_address,
+            |_, _| Ok(()),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_with_address_derivation_and_validation<D, F>(
+        class_hash: ClassHash,
+        account_tx_fields: VersionSpecificAccountTxFields,
+        version: Felt252,
+        nonce: Felt252,
+        constructor_calldata: Vec<Felt252>,
+        signature: Vec<Felt252>,
+        contract_address_salt: Felt252,
+        chain_id: Felt252,
+        derive_address: D,
+        validate_address: F,
+    ) -> Result<Self, TransactionError>
+    where
+        D: FnOnce(&Felt252, &Felt252, &[Felt252], &Address) -> Result<Felt252, TransactionError>,
+        F: FnOnce(&Address, &ClassHash) -> Result<(), TransactionError>,
+    {
         let version = get_tx_version(version);
         check_account_tx_fields_version(&account_tx_fields, version)?;
-        let contract_address = Address(calculate_contract_address(
+        let contract_address = Address(derive_address(
             &contract_address_salt,
             &Felt252::from_bytes_be(&class_hash.0),
             &constructor_calldata,
-            Address(Felt252::ZERO),
+            &Address(Felt252::ZERO),
         )?);
+        validate_address(&contract_address, &class_hash)?;
 
         let hash_value = calculate_deploy_account_transaction_hash(
             version,
@@ -132,6 +502,8 @@ impl DeployAccount {
             skip_validate: false,
             skip_fee_transfer: false,
             skip_nonce_check: false,
+            chain_id: Some(chain_id),
+            options: DeployAccountOptions::default(),
         })
     }
 
@@ -170,667 +542,6335 @@ impl DeployAccount {
             skip_validate: false,
             skip_fee_transfer: false,
             skip_nonce_check: false,
+            chain_id: None,
+            options: DeployAccountOptions::default(),
         })
     }
 
-    pub fn get_state_selector(&self, _block_context: BlockContext) -> StateSelector {
-        StateSelector {
-            contract_addresses: vec![self.contract_address.clone()],
-            class_hashes: vec![self.class_hash],
+    /// Creates a new DeployAccount like [DeployAccount::new], paying its fee with `fee_type`
+    /// regardless of its account tx fields.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_fee_type_override(
+        class_hash: ClassHash,
+        account_tx_fields: VersionSpecificAccountTxFields,
+        version: Felt252,
+        nonce: Felt252,
+        constructor_calldata: Vec<Felt252>,
+        signature: Vec<Felt252>,
+        contract_address_salt: Felt252,
+        chain_id: Felt252,
+        fee_type: FeeType,
+    ) -> Result<Self, TransactionError> {
+        let mut deploy = Self::new(
+            class_hash,
+            account_tx_fields,
+            version,
+            nonce,
+            constructor_calldata,
+            signature,
+            contract_address_salt,
+            chain_id,
+        )?;
+        deploy.options.fee_type_override = Some(fee_type);
+        Ok(deploy)
+    }
+
+    /// Creates a new DeployAccount like [DeployAccount::new], checking that it deploys the
+    /// account at `counterfactual_address`, e.g. an address that was funded ahead of the
+    /// deployment.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_counterfactual_address(
+        class_hash: ClassHash,
+        account_tx_fields: VersionSpecificAccountTxFields,
+        version: Felt252,
+        nonce: Felt252,
+        constructor_calldata: Vec<Felt252>,
+        signature: Vec<Felt252>,
+        contract_address_salt: Felt252,
+        chain_id: Felt252,
+        counterfactual_address: Address,
+    ) -> Result<Self, TransactionError> {
+        Self::new_with_address_validation(
+            class_hash,
+            account_tx_fields,
+            version,
+            nonce,
+            constructor_calldata,
+            signature,
+            contract_address_salt,
+            chain_id,
+            |contract_address, _| {
+                if *contract_address != counterfactual_address {
+                    return Err(TransactionError::CounterfactualAddressMismatch(
+                        counterfactual_address,
+                        contract_address.clone(),
+                    ));
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns the worst-case fee this transaction may be charged.
+    /// For V3 transactions it is the sum of `max_amount * max_price_per_unit` over the resource
+    /// bounds plus the tip, for older versions it is the max fee.
+    /// Returns `None` if the computation overflows.
+    pub fn max_possible_fee(&self) -> Option<u128> {
+        match &self.account_tx_fields {
+            VersionSpecificAccountTxFields::Deprecated(max_fee) => Some(*max_fee),
+            VersionSpecificAccountTxFields::Current(current) => [
+                &current.resource_bounds.l1_gas,
+                &current.resource_bounds.l2_gas,
+            ]
+            .into_iter()
+            .flatten()
+            .try_fold(current.tip as u128, |acc, bounds| {
+                (bounds.max_amount as u128)
+                    .checked_mul(bounds.max_price_per_unit)
+                    .and_then(|fee| acc.checked_add(fee))
+            }),
         }
     }
 
-    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
-        tx_type = ?TransactionType::DeployAccount,
-        self.version = ?self.version,
-        self.class_hash = ?self.class_hash,
-        self.hash_value = ?self.hash_value,
-        self.contract_address = ?self.contract_address,
-        self.contract_address_salt = ?self.contract_address_salt,
-        self.nonce = ?self.nonce,
-    ))]
-    pub fn execute<S: StateReader, C: ContractClassCache>(
-        &self,
-        state: &mut CachedState<S, C>,
-        block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
-    ) -> Result<TransactionExecutionInfo, TransactionError> {
-        if self.version != Felt252::ONE {
-            return Err(TransactionError::UnsupportedTxVersion(
-                "DeployAccount".to_string(),
-                self.version,
-                vec![1],
-            ));
+    /// Returns whether the transaction can't be charged any fee: its max fee is zero, or, for V3
+    /// transactions, every resource bound is zero.
+    fn has_zero_fee_bounds(&self) -> bool {
+        match &self.account_tx_fields {
+            VersionSpecificAccountTxFields::Deprecated(max_fee) => max_fee.is_zero(),
+            VersionSpecificAccountTxFields::Current(current) => [
+                &current.resource_bounds.l1_gas,
+                &current.resource_bounds.l2_gas,
+            ]
+            .into_iter()
+            .flatten()
+            .all(|bounds| bounds.max_amount == 0 || bounds.max_price_per_unit == 0),
         }
+    }
 
-        self.handle_nonce(state)?;
+    /// Predicts whether executing this transaction will transfer a fee to the sequencer.
+    /// No fee is charged when the fee transfer is skipped or the max fee is zero, or, for V3
+    /// transactions, every resource bound is zero; a revert at runtime isn't taken into account.
+    pub fn will_charge_fee(&self) -> bool {
+        !self.skip_fee_transfer && !self.has_zero_fee_bounds()
+    }
 
-        if !self.skip_fee_transfer {
-            self.check_fee_balance(state, block_context, &FeeType::Eth)?;
-        }
+    /// Returns whether the account needs a fee token balance covering the max fee for the
+    /// transaction to go through, e.g. to tell if it must be funded before being submitted.
+    /// The balance is checked whenever a fee may be charged, see [Self::will_charge_fee].
+    pub fn requires_funded_balance(&self) -> bool {
+        self.will_charge_fee()
+    }
 
-        let mut transactional_state = state.create_transactional()?;
-        let tx_exec_info = self.apply(
-            &mut transactional_state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
-        );
-        #[cfg(feature = "replay_benchmark")]
-        // Add initial values to cache despite tx outcome
-        {
-            state.cache_mut().storage_initial_values_mut().extend(
-                transactional_state
-                    .cache()
-                    .storage_initial_values
-                    .clone()
-                    .into_iter(),
-            );
-            state.cache_mut().class_hash_initial_values_mut().extend(
-                transactional_state
-                    .cache()
-                    .class_hash_initial_values
-                    .clone()
-                    .into_iter(),
-            );
-        }
-        let mut tx_exec_info = tx_exec_info?;
+    /// Returns the number of felts in the transaction's signature.
+    pub fn signature_len(&self) -> usize {
+        self.signature.len()
+    }
 
-        let actual_fee =
-            calculate_tx_fee(&tx_exec_info.actual_resources, block_context, &FeeType::Eth)?;
+    /// Returns the type of this transaction.
+    pub const fn transaction_type(&self) -> TransactionType {
+        TransactionType::DeployAccount
+    }
 
-        if let Some(revert_error) = tx_exec_info.revert_error.clone() {
-            // execution error
-            tx_exec_info = tx_exec_info.to_revert_error(&revert_error);
-        } else if actual_fee > self.account_tx_fields.max_fee() {
-            // max_fee exceeded
-            tx_exec_info = tx_exec_info.to_revert_error(
-                format!(
-                    "Calculated fee ({}) exceeds max fee ({})",
-                    actual_fee,
-                    self.account_tx_fields.max_fee()
-                )
-                .as_str(),
-            );
-        } else {
-            state
-                .apply_state_update(&StateDiff::from_cached_state(transactional_state.cache())?)?;
+    /// Returns the intermediate inputs of the derivation of the contract address, recomputing
+    /// the address from them. Useful to track down address mismatches with wallets and SDKs.
+    pub fn explain_address(&self) -> Result<AddressDerivation, TransactionError> {
+        let class_hash = Felt252::from_bytes_be(&self.class_hash.0);
+        let deployer_address = Address(Felt252::ZERO);
+        Ok(AddressDerivation {
+            salt: self.contract_address_salt,
+            class_hash,
+            constructor_calldata_hash: compute_hash_on_elements(&self.constructor_calldata)?,
+            contract_address: Address(calculate_contract_address(
+                &self.contract_address_salt,
+                &class_hash,
+                &self.constructor_calldata,
+                deployer_address.clone(),
+            )?),
+            deployer_address,
+        })
+    }
+
+    /// Returns the fee type used to pay for this transaction: the overriding fee type if set,
+    /// otherwise STRK for V3 transactions and ETH for older versions.
+    pub fn fee_type(&self) -> FeeType {
+        if let Some(fee_type) = self.options.fee_type_override {
+            return fee_type;
         }
+        match self.account_tx_fields {
+            VersionSpecificAccountTxFields::Deprecated(_) => FeeType::Eth,
+            VersionSpecificAccountTxFields::Current(_) => FeeType::Strk,
+        }
+    }
 
-        let mut tx_execution_context =
-            self.get_execution_context(block_context.invoke_tx_max_n_steps);
-        let (fee_transfer_info, actual_fee) = charge_fee(
-            state,
-            &tx_exec_info.actual_resources,
-            block_context,
-            self.account_tx_fields.max_fee(),
-            &mut tx_execution_context,
-            self.skip_fee_transfer,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )?;
+    /// Returns the address of the fee token this transaction will pay its fee with.
+    pub fn fee_token_address(&self, block_context: &BlockContext) -> Address {
+        block_context
+            .starknet_os_config
+            .fee_token_address
+            .get_by_fee_type(&self.fee_type())
+            .clone()
+    }
 
-        tx_exec_info.set_fee_info(actual_fee, fee_transfer_info);
+    /// Returns what must be seeded into the state for this transaction to succeed: the account
+    /// class must be declared, and the account funded with at least the max fee unless no fee
+    /// is charged.
+    pub fn required_preconditions(&self, block_context: &BlockContext) -> Preconditions {
+        let min_balance = if self.skip_fee_transfer {
+            0
+        } else {
+            self.account_tx_fields.max_fee()
+        };
+        Preconditions {
+            class_hash: self.class_hash,
+            fee_token_address: self.fee_token_address(block_context),
+            min_balance,
+        }
+    }
 
-        Ok(tx_exec_info)
+    /// Returns whether no contract is deployed at the account address yet, without running the
+    /// constructor or the validation.
+    pub fn address_is_available<S: StateReader>(
+        &self,
+        state: &S,
+    ) -> Result<bool, TransactionError> {
+        Ok(state.get_class_hash_at(&self.contract_address)? == *UNINITIALIZED_CLASS_HASH)
     }
 
-    fn constructor_entry_points_empty(
+    /// Returns the JSON ABI of the class the transaction deploys, as found in `state`, e.g. for
+    /// tooling to display the account's methods.
+    /// Only deprecated classes carry their ABI, so `None` is returned for Cairo 1 classes and
+    /// for deprecated classes without one.
+    pub fn deployed_class_abi<S: StateReader>(
         &self,
-        contract_class: CompiledClass,
-    ) -> Result<bool, StateError> {
-        Ok(match contract_class {
-            CompiledClass::Deprecated(class) => class
-                .entry_points_by_type
-                .get(&EntryPointType::Constructor)
-                .ok_or(ContractClassError::NoneEntryPointType)?
-                .is_empty(),
-            CompiledClass::Casm { casm: class, .. } => {
-                class.entry_points_by_type.constructor.is_empty()
-            }
-        })
+        state: &S,
+    ) -> Result<Option<String>, TransactionError> {
+        match state.get_contract_class(&self.class_hash)? {
+            CompiledClass::Deprecated(contract_class) => contract_class
+                .abi()
+                .as_ref()
+                .map(|abi| {
+                    serde_json::to_string(abi)
+                        .map_err(|err| TransactionError::CustomError(err.to_string()))
+                })
+                .transpose(),
+            CompiledClass::Casm { .. } => Ok(None),
+        }
     }
 
-    /// Execute a call to the cairo-vm using the accounts_validation.cairo contract to validate
-    /// the contract that is being declared. Then it returns the transaction execution info of the run.
-    fn apply<S: StateReader, C: ContractClassCache>(
+    /// Returns the low and high storage keys of the account's balance in the fee token
+    /// contract, as read by [State::get_fee_token_balance].
+    pub fn fee_balance_storage_key(&self) -> (Felt252, Felt252) {
+        // The account address is a felt, so it always converts to a valid field element.
+        get_uint256_storage_var_addresses("ERC20_balances", &[self.contract_address.0])
+            .expect("an address is always a valid field element")
+    }
+
+    /// Returns a copy of this V3 transaction with L1 gas resource bounds covering `estimate`
+    /// plus a safety margin of `margin_percent` on both the gas amount and its price.
+    /// The transaction hash is recomputed, so the transaction needs to be signed again.
+    pub fn with_bounds_from_estimate(
         &self,
-        state: &mut CachedState<S, C>,
-        block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
-    ) -> Result<TransactionExecutionInfo, TransactionError> {
-        let contract_class = state.get_contract_class(&self.class_hash)?;
+        estimate: &FeeEstimate,
+        margin_percent: u64,
+    ) -> Result<Self, TransactionError> {
+        let VersionSpecificAccountTxFields::Current(current) = &self.account_tx_fields else {
+            return Err(TransactionError::UnsupportedTxVersion(
+                "DeployAccount".to_string(),
+                self.version,
+                vec![3],
+            ));
+        };
+        let chain_id = self.chain_id.ok_or_else(|| {
+            TransactionError::CustomError(
+                "Can't recompute the hash of a DeployAccount created with a given hash".to_string(),
+            )
+        })?;
 
-        state.deploy_contract(self.contract_address.clone(), self.class_hash)?;
+        let with_margin = |value: u128| {
+            value
+                .saturating_mul(100 + margin_percent as u128)
+                .saturating_add(99)
+                / 100
+        };
+        let mut current = current.clone();
+        current.resource_bounds.l1_gas = Some(ResourceBounds {
+            max_amount: with_margin(estimate.gas as u128)
+                .try_into()
+                .unwrap_or(u64::MAX),
+            max_price_per_unit: with_margin(estimate.gas_price),
+        });
+        let account_tx_fields = VersionSpecificAccountTxFields::Current(current);
 
-        let mut resources_manager = ExecutionResourcesManager::default();
-        let constructor_call_info = self.handle_constructor(
-            contract_class,
-            state,
-            block_context,
-            &mut resources_manager,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+        let hash_value = calculate_deploy_account_transaction_hash(
+            self.version,
+            &self.contract_address,
+            Felt252::from_bytes_be(&self.class_hash.0),
+            &self.constructor_calldata,
+            account_tx_fields.max_fee(),
+            self.nonce,
+            self.contract_address_salt,
+            chain_id,
         )?;
 
-        let validate_info = if self.skip_validate {
-            None
-        } else {
-            self.run_validate_entrypoint(
-                state,
-                block_context,
-                &mut resources_manager,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            )?
-        };
+        Ok(Self {
+            account_tx_fields,
+            hash_value,
+            ..self.clone()
+        })
+    }
 
-        let actual_resources = calculate_tx_resources(
-            resources_manager,
-            &[Some(constructor_call_info.clone()), validate_info.clone()],
-            TransactionType::DeployAccount,
-            state.count_actual_state_changes(Some((
-                (block_context
-                    .starknet_os_config
-                    .fee_token_address
-                    .get_by_fee_type(&FeeType::Eth)),
-                &self.contract_address,
-            )))?,
-            None,
-            0,
-        )
-        .map_err::<TransactionError, _>(|_| TransactionError::ResourcesCalculation)?;
+    /// Returns a copy of this transaction with the given nonce, for resubmitting it.
+    /// The contract address doesn't depend on the nonce and is kept as is; only the transaction
+    /// hash is recomputed, so the transaction needs to be signed again.
+    pub fn with_nonce(&self, nonce: Felt252, chain_id: Felt252) -> Result<Self, TransactionError> {
+        let hash_value = calculate_deploy_account_transaction_hash(
+            self.version,
+            &self.contract_address,
+            Felt252::from_bytes_be(&self.class_hash.0),
+            &self.constructor_calldata,
+            self.account_tx_fields.max_fee(),
+            nonce,
+            self.contract_address_salt,
+            chain_id,
+        )?;
 
-        Ok(TransactionExecutionInfo::new_without_fee_info(
-            validate_info,
-            Some(constructor_call_info),
-            None,
-            actual_resources,
-            Some(TransactionType::DeployAccount),
-        ))
+        Ok(Self {
+            nonce,
+            hash_value,
+            chain_id: Some(chain_id),
+            ..self.clone()
+        })
     }
 
-    /// Handles the constructor of a contract, executes it if necessary.
-    pub fn handle_constructor<S: StateReader, C: ContractClassCache>(
+    /// Returns the L1 and L2 gas resource bounds of a V3 transaction, e.g. to display them, or
+    /// `None` for older versions, which set a max fee instead.
+    pub fn resource_bounds(&self) -> Option<&ResourceBoundsMapping> {
+        self.account_tx_fields.resource_bounds()
+    }
+
+    /// Returns the nonce of the account after this transaction is successfully executed.
+    pub fn nonce_after(&self) -> Felt252 {
+        self.nonce + Felt252::ONE
+    }
+
+    /// Returns a copy of this transaction with the given signature, e.g. to reuse the signature
+    /// of a template transaction. The signature isn't part of the transaction hash, so the hash
+    /// is kept as is, unless it was computed for a chain other than `chain_id`.
+    pub fn with_signature(
         &self,
-        contract_class: CompiledClass,
-        state: &mut CachedState<S, C>,
-        block_context: &BlockContext,
-        resources_manager: &mut ExecutionResourcesManager,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
-    ) -> Result<CallInfo, TransactionError> {
-        if self.constructor_entry_points_empty(contract_class)? {
-            if !self.constructor_calldata.is_empty() {
-                return Err(TransactionError::EmptyConstructorCalldata);
+        signature: Vec<Felt252>,
+        chain_id: Felt252,
+    ) -> Result<Self, TransactionError> {
+        let transaction = match self.chain_id {
+            Some(hashed_chain_id) if hashed_chain_id != chain_id => {
+                self.with_nonce(self.nonce, chain_id)?
             }
+            _ => self.clone(),
+        };
+        Ok(Self {
+            signature,
+            ..transaction
+        })
+    }
 
-            Ok(CallInfo::empty_constructor_call(
-                self.contract_address.clone(),
-                Address(Felt252::ZERO),
-                Some(self.class_hash),
-            ))
-        } else {
-            self.run_constructor_entrypoint(
-                state,
-                block_context,
-                resources_manager,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            )
-        }
+    /// Returns the hash the account signs, which is the transaction hash.
+    /// Signature schemes vary between accounts, so the signature can be checked against this hash
+    /// externally; [DeployAccount::verify_stark_signature] covers the default stark-curve scheme.
+    pub fn message_hash(&self) -> Felt252 {
+        self.hash_value
     }
 
-    /// Handles the nonce of a transaction, verifies if it is valid and increments it.
-    fn handle_nonce<S: State + StateReader>(&self, state: &mut S) -> Result<(), TransactionError> {
-        if self.version.is_zero() {
-            return Ok(());
+    /// Checks that the signature is a stark-curve ECDSA signature `[r, s]` of the transaction hash
+    /// made with the key matching `public_key`, without running the account's validate entry point.
+    pub fn verify_stark_signature(&self, public_key: Felt252) -> bool {
+        let [r, s] = self.signature.as_slice() else {
+            return false;
+        };
+        let to_field_element = |felt: &Felt252| FieldElement::from_bytes_be(&felt.to_bytes_be());
+        match (
+            to_field_element(&public_key),
+            to_field_element(&self.hash_value),
+            to_field_element(r),
+            to_field_element(s),
+        ) {
+            (Ok(public_key), Ok(message_hash), Ok(r), Ok(s)) => {
+                verify(&public_key, &message_hash, &r, &s).unwrap_or(false)
+            }
+            _ => false,
         }
+    }
 
-        // In blockifier, get_nonce_at returns zero if no entry is found.
-        let current_nonce = state.get_nonce_at(&self.contract_address)?;
-        if current_nonce != self.nonce && !self.skip_nonce_check {
-            return Err(TransactionError::InvalidTransactionNonce(
-                current_nonce.to_string(),
-                self.nonce.to_string(),
-            ));
+    pub fn get_state_selector(&self, _block_context: BlockContext) -> StateSelector {
+        StateSelector {
+            contract_addresses: vec![self.contract_address.clone()],
+            class_hashes: vec![self.class_hash],
         }
-        state.increment_nonce(&self.contract_address)?;
-        Ok(())
     }
 
-    fn check_fee_balance<S: State + StateReader>(
+    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
+        tx_type = ?TransactionType::DeployAccount,
+        self.version = ?self.version,
+        self.class_hash = ?self.class_hash,
+        self.hash_value = ?self.hash_value,
+        self.contract_address = ?self.contract_address,
+        self.contract_address_salt = ?self.contract_address_salt,
+        self.nonce = ?self.nonce,
+    ))]
+    pub fn execute<S: StateReader, C: ContractClassCache>(
         &self,
-        state: &mut S,
+        state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        fee_type: &FeeType,
-    ) -> Result<(), TransactionError> {
-        if self.account_tx_fields.max_fee().is_zero() {
-            return Ok(());
-        }
-        let minimal_fee = self.estimate_minimal_fee(block_context)?;
-        // Check max fee is at least the estimated constant overhead.
-        if self.account_tx_fields.max_fee() < minimal_fee {
-            return Err(TransactionError::MaxFeeTooLow(
-                self.account_tx_fields.max_fee(),
-                minimal_fee,
-            ));
-        }
-        // Check that the current balance is high enough to cover the max_fee
-        let (balance_low, balance_high) =
-            state.get_fee_token_balance(block_context, self.contract_address(), fee_type)?;
-        // The fee is at most 128 bits, while balance is 256 bits (split into two 128 bit words).
-        if balance_high.is_zero() && balance_low < Felt252::from(self.account_tx_fields.max_fee()) {
-            return Err(TransactionError::MaxFeeExceedsBalance(
-                self.account_tx_fields.max_fee(),
-                balance_low,
-                balance_high,
-            ));
-        }
-        Ok(())
-    }
-
-    fn estimate_minimal_fee(&self, block_context: &BlockContext) -> Result<u128, TransactionError> {
-        let n_estimated_steps = ESTIMATED_DEPLOY_ACCOUNT_STEPS;
-        let onchain_data_length = get_onchain_data_segment_length(&StateChangesCount {
-            n_storage_updates: 1,
-            n_class_hash_updates: 1,
-            n_compiled_class_hash_updates: 0,
-            n_modified_contracts: 1,
-        });
-        let resources = HashMap::from([
-            (
-                "l1_gas_usage".to_string(),
-                onchain_data_length * SHARP_GAS_PER_MEMORY_WORD,
-            ),
-            ("n_steps".to_string(), n_estimated_steps),
-        ]);
-        calculate_tx_fee(&resources, block_context, &FeeType::Eth)
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.execute_with_prefetched_values(
+            state,
+            block_context,
+            None,
+            None,
+            None,
+            None,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )
     }
 
-    pub fn run_constructor_entrypoint<S: StateReader, C: ContractClassCache>(
+    /// Executes the transaction using a nonce that was already fetched by the caller.
+    /// The supplied nonce is trusted for the nonce check, so the state is not queried for it,
+    /// but it is still incremented.
+    pub fn execute_with_prefetched_nonce<S: StateReader, C: ContractClassCache>(
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        resources_manager: &mut ExecutionResourcesManager,
+        current_nonce: Felt252,
         #[cfg(feature = "cairo-native")] program_cache: Option<
             Rc<RefCell<ProgramCache<'_, ClassHash>>>,
         >,
-    ) -> Result<CallInfo, TransactionError> {
-        let entry_point = ExecutionEntryPoint::new(
-            self.contract_address.clone(),
-            self.constructor_calldata.clone(),
-            *CONSTRUCTOR_ENTRY_POINT_SELECTOR,
-            Address(Felt252::ZERO),
-            EntryPointType::Constructor,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.execute_with_prefetched_values(
+            state,
+            block_context,
+            Some(current_nonce),
             None,
             None,
-            INITIAL_GAS_COST,
-        );
-
-        let ExecutionResult { call_info, .. } = if self.skip_execute {
-            ExecutionResult::default()
-        } else {
-            entry_point.execute(
-                state,
-                block_context,
-                resources_manager,
-                &mut self.get_execution_context(block_context.validate_max_n_steps),
-                false,
-                block_context.validate_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            )?
-        };
-
-        let call_info = verify_no_calls_to_other_contracts(&call_info)
-            .map_err(|_| TransactionError::InvalidContractCall)?;
-        Ok(call_info)
-    }
-
-    pub fn get_execution_context(&self, n_steps: u64) -> TransactionExecutionContext {
-        TransactionExecutionContext::new(
-            self.contract_address.clone(),
-            self.hash_value,
-            self.signature.clone(),
-            self.account_tx_fields.clone(),
-            self.nonce,
-            n_steps,
-            self.version,
+            None,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
         )
     }
 
-    pub fn run_validate_entrypoint<S: StateReader, C: ContractClassCache>(
+    /// Executes the transaction using the fee token balance of the account, as a (low, high)
+    /// pair, that was already fetched by the caller. The supplied balance is used for the fee
+    /// balance check instead of reading it from the fee token contract storage.
+    pub fn execute_with_prefetched_balance<S: StateReader, C: ContractClassCache>(
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        resources_manager: &mut ExecutionResourcesManager,
+        fee_token_balance: (Felt252, Felt252),
         #[cfg(feature = "cairo-native")] program_cache: Option<
             Rc<RefCell<ProgramCache<'_, ClassHash>>>,
         >,
-    ) -> Result<Option<CallInfo>, TransactionError> {
-        let call = ExecutionEntryPoint::new(
-            self.contract_address.clone(),
-            [
-                Felt252::from_bytes_be(&self.class_hash.0),
-                self.contract_address_salt,
-            ]
-            .into_iter()
-            .chain(self.constructor_calldata.iter().cloned())
-            .collect(),
-            *VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR,
-            Address(Felt252::ZERO),
-            EntryPointType::External,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.execute_with_prefetched_values(
+            state,
+            block_context,
             None,
+            Some(fee_token_balance),
             None,
-            INITIAL_GAS_COST,
+            None,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )
+    }
+
+    /// Executes the transaction computing its fees with `fee_strategy`. If it's `None`, the
+    /// fees are computed by the default strategy, rounded as set by `fee_rounding`.
+    pub fn execute_with_fee_strategy<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        fee_strategy: Option<&dyn FeeStrategy>,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.execute_with_prefetched_values(
+            state,
+            block_context,
+            None,
+            None,
+            fee_strategy,
+            None,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )
+    }
+
+    /// Executes the transaction, reporting every write the deployment makes to the state to
+    /// `on_state_write`: the deployment itself, then the writes made by the constructor.
+    /// The writes are reported as they are applied to the transactional state, so they are
+    /// discarded if the transaction ends up failing.
+    pub fn execute_with_state_write_callback<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        on_state_write: &mut dyn FnMut(StateWriteEvent),
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.execute_with_prefetched_values(
+            state,
+            block_context,
+            None,
+            None,
+            None,
+            Some(on_state_write),
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )
+    }
+
+    fn execute_with_prefetched_values<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        prefetched_nonce: Option<Felt252>,
+        prefetched_balance: Option<(Felt252, Felt252)>,
+        fee_strategy: Option<&dyn FeeStrategy>,
+        on_state_write: Option<&mut dyn FnMut(StateWriteEvent)>,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.check_deadline()?;
+        Self::check_fee_token_addresses(block_context)?;
+        let block_context: &BlockContext =
+            &self.block_context_with_recorded_gas_prices(block_context);
+        let fee_strategy = fee_strategy.unwrap_or(&self.options.fee_rounding);
+        if let Err(error) =
+            self.handle_pre_execution(state, block_context, prefetched_nonce, prefetched_balance)
+        {
+            return match error {
+                TransactionError::MaxFeeExceedsBalance(..)
+                    if block_context.insufficient_balance_reverts =>
+                {
+                    // The reverted transaction is still included, so its nonce is consumed.
+                    if block_context.increment_nonce_after_execution {
+                        self.increment_nonce(state)?;
+                    }
+                    self.revert_insufficient_balance(
+                        state,
+                        block_context,
+                        &error,
+                        fee_strategy,
+                        #[cfg(feature = "cairo-native")]
+                        program_cache,
+                    )
+                }
+                error => Err(error),
+            };
+        }
+
+        let mut transactional_state = state.create_transactional()?;
+        self.apply_storage_overlay(&mut transactional_state);
+        let tx_exec_info = self.apply(
+            &mut transactional_state,
+            block_context,
+            on_state_write,
+            #[cfg(feature = "cairo-native")]
+            program_cache.clone(),
         );
+        #[cfg(feature = "replay_benchmark")]
+        // Add initial values to cache despite tx outcome
+        {
+            state.cache_mut().storage_initial_values_mut().extend(
+                transactional_state
+                    .cache()
+                    .storage_initial_values
+                    .clone()
+                    .into_iter(),
+            );
+            state.cache_mut().class_hash_initial_values_mut().extend(
+                transactional_state
+                    .cache()
+                    .class_hash_initial_values
+                    .clone()
+                    .into_iter(),
+            );
+        }
+        // A failed execution leaves the nonce untouched when it is incremented after execution.
+        let mut tx_exec_info = tx_exec_info?;
+        self.check_deadline()?;
+        if block_context.increment_nonce_after_execution {
+            self.increment_nonce(state)?;
+        }
+        // Exceeding the events limit reverts the transaction, so the fee is still charged.
+        if tx_exec_info.revert_error.is_none() {
+            if let Err(error) = Self::check_max_events(&tx_exec_info, block_context) {
+                tx_exec_info.revert_error = Some(error.to_string());
+            }
+        }
 
-        let ExecutionResult { call_info, .. } = if self.skip_execute {
-            ExecutionResult::default()
-        } else {
-            call.execute(
-                state,
-                block_context,
-                resources_manager,
-                &mut self.get_execution_context(block_context.validate_max_n_steps),
-                false,
-                block_context.validate_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            )?
-        };
+        let actual_fee = fee_strategy.calculate_tx_fee(
+            &tx_exec_info.actual_resources,
+            block_context,
+            &self.fee_type(),
+        )?;
 
-        // Validate the return data
-        let class_hash = state.get_class_hash_at(&self.contract_address)?;
-        let contract_class = state
-            .get_contract_class(&class_hash)
-            .map_err(|_| TransactionError::MissingCompiledClass)?;
-        if matches!(
-            contract_class,
-            CompiledClass::Casm {
-                sierra: Some(_),
-                ..
+        if let Some(revert_error) = tx_exec_info.revert_error.clone() {
+            // execution error
+            tx_exec_info = tx_exec_info.to_revert_error(&revert_error);
+        } else if actual_fee > self.account_tx_fields.max_fee()
+            && !self.options.report_fee_overage_without_revert
+        {
+            // max_fee exceeded
+            tx_exec_info = tx_exec_info.to_revert_error(
+                format!(
+                    "Calculated fee ({}) exceeds max fee ({})",
+                    actual_fee,
+                    self.account_tx_fields.max_fee()
+                )
+                .as_str(),
+            );
+        } else {
+            if actual_fee > self.account_tx_fields.max_fee() {
+                tx_exec_info.fee_overage = Some(actual_fee - self.account_tx_fields.max_fee());
             }
-        ) {
-            // The account contract class is a Cairo 1.0 contract; the `validate` entry point should
-            // return `VALID`.
-            if !call_info
-                .as_ref()
-                .map(|ci| ci.retdata == vec![*VALIDATE_RETDATA])
-                .unwrap_or_default()
-            {
-                return Err(TransactionError::WrongValidateRetdata);
+            let state_diff = StateDiff::from_cached_state(transactional_state.cache())?;
+            if self.options.record_commitment_leaf_updates {
+                tx_exec_info.commitment_leaf_updates = Some(state_diff.contract_leaf_updates());
             }
+            state.apply_state_update(&state_diff)?;
         }
 
-        verify_no_calls_to_other_contracts(&call_info)
-            .map_err(|_| TransactionError::InvalidContractCall)?;
+        self.handle_fee(
+            state,
+            block_context,
+            &mut tx_exec_info,
+            fee_strategy,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )?;
 
-        Ok(call_info)
+        Ok(tx_exec_info)
     }
 
-    pub fn create_for_simulation(
-        &self,
-        skip_validate: bool,
-        skip_execute: bool,
-        skip_fee_transfer: bool,
-        ignore_max_fee: bool,
-        skip_nonce_check: bool,
-    ) -> Transaction {
-        let tx = DeployAccount {
-            skip_validate,
-            skip_execute,
-            skip_fee_transfer,
-            account_tx_fields: if ignore_max_fee {
-                if let VersionSpecificAccountTxFields::Current(current) = &self.account_tx_fields {
-                    let mut current_fields = current.clone();
-                    current_fields.l1_resource_bounds = Some(ResourceBounds {
-                        max_amount: u64::MAX,
-                        max_price_per_unit: u128::MAX,
-                    });
-                    VersionSpecificAccountTxFields::Current(current_fields)
-                } else {
-                    VersionSpecificAccountTxFields::new_deprecated(u128::MAX)
+    /// Executes `transactions` in order, counting how many succeeded and how many failed with
+    /// each [TransactionError] variant. Successful deploys are kept in `state`, so later
+    /// transactions observe them.
+    pub fn simulate_all<S: StateReader, C: ContractClassCache>(
+        transactions: &[DeployAccount],
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> SimulationReport {
+        let mut report = SimulationReport::default();
+        for transaction in transactions {
+            report.record(&transaction.execute(
+                state,
+                block_context,
+                #[cfg(feature = "cairo-native")]
+                program_cache.clone(),
+            ));
+        }
+        report
+    }
+
+    /// Splits `transactions` into the ones to keep and the ones to drop, keeping a single
+    /// transaction per contract address, as only one deploy to an address can succeed.
+    /// The transaction with the highest max fee is kept, the earliest one on ties. Kept
+    /// transactions are returned in the order their address first appears.
+    pub fn dedup_by_address(
+        transactions: Vec<DeployAccount>,
+    ) -> (Vec<DeployAccount>, Vec<DeployAccount>) {
+        let mut kept: Vec<DeployAccount> = Vec::new();
+        let mut dropped = Vec::new();
+        let mut kept_index_by_address: HashMap<Address, usize> = HashMap::new();
+        for transaction in transactions {
+            match kept_index_by_address.get(&transaction.contract_address) {
+                Some(&index) => {
+                    if transaction.account_tx_fields.max_fee()
+                        > kept[index].account_tx_fields.max_fee()
+                    {
+                        dropped.push(std::mem::replace(&mut kept[index], transaction));
+                    } else {
+                        dropped.push(transaction);
+                    }
                 }
-            } else {
-                self.account_tx_fields.clone()
-            },
-            skip_nonce_check,
-            ..self.clone()
-        };
+                None => {
+                    kept_index_by_address.insert(transaction.contract_address.clone(), kept.len());
+                    kept.push(transaction);
+                }
+            }
+        }
+        (kept, dropped)
+    }
 
-        Transaction::DeployAccount(tx)
+    /// Declares the `classes` in `state` ahead of a batch of deploys, handling the classes
+    /// already present as set by `policy`, e.g. when several batches share a class.
+    /// Returns how many classes were declared.
+    pub fn declare_classes<S: StateReader, C: ContractClassCache>(
+        state: &mut CachedState<S, C>,
+        classes: &[(ClassHash, CompiledClass)],
+        policy: DuplicateClassPolicy,
+    ) -> Result<usize, TransactionError> {
+        let mut declared = 0;
+        for (class_hash, contract_class) in classes {
+            if State::get_contract_class(state, class_hash).is_ok() {
+                match policy {
+                    DuplicateClassPolicy::Skip => continue,
+                    DuplicateClassPolicy::Replace => {}
+                    DuplicateClassPolicy::Reject => {
+                        return Err(TransactionError::ClassAlreadyDeclared(*class_hash))
+                    }
+                }
+            }
+            state.set_contract_class(class_hash, contract_class)?;
+            declared += 1;
+        }
+        Ok(declared)
     }
 
-    pub fn from_sn_api_transaction(
-        value: starknet_api::transaction::DeployAccountTransaction,
-        tx_hash: Felt252,
-    ) -> Result<Self, TransactionError> {
-        let max_fee = match value {
-            starknet_api::transaction::DeployAccountTransaction::V1(ref tx) => tx.max_fee,
-            starknet_api::transaction::DeployAccountTransaction::V3(_) => {
-                return Err(TransactionError::UnsuportedV3Transaction)
+    /// Returns the smallest of the `candidates` max fees with which the transaction executes
+    /// without being rejected or reverted, or `None` if no candidate succeeds.
+    /// Each probe is rebuilt with the candidate max fee, which changes the transaction hash, so
+    /// it is re-signed with `sign`, given the probe's hash. Without `sign` the transaction's
+    /// signature is kept, which only suits accounts that don't check it in `__validate_deploy__`.
+    /// Candidates above the account balance are left out, since the balance check rejects them;
+    /// below it, success is assumed to be monotonic in the max fee, so candidates are
+    /// binary-searched. Every probe runs on a transactional copy of `state`, which is left
+    /// untouched.
+    /// Candidates are V1 max fees, so V3 transactions are rejected.
+    pub fn estimate_min_successful_fee<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &CachedState<S, C>,
+        block_context: &BlockContext,
+        candidates: &[u128],
+        sign: Option<&dyn Fn(Felt252) -> Vec<Felt252>>,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<Option<u128>, TransactionError> {
+        if self.version != Felt252::ONE {
+            return Err(TransactionError::UnsupportedTxVersion(
+                "DeployAccount".to_string(),
+                self.version,
+                vec![1],
+            ));
+        }
+        let balance_ceiling = if self.skip_fee_transfer {
+            u128::MAX
+        } else {
+            let (balance_low, balance_high) = state.create_transactional()?.get_fee_token_balance(
+                block_context,
+                &self.contract_address,
+                &self.fee_type(),
+            )?;
+            match balance_low.to_u128() {
+                Some(balance_low) if balance_high.is_zero() => balance_low,
+                _ => u128::MAX,
             }
         };
-        let version = Felt252::from_bytes_be_slice(value.version().0.bytes());
-        let nonce = Felt252::from_bytes_be_slice(value.nonce().0.bytes());
-        let class_hash: ClassHash = ClassHash(value.class_hash().0.bytes().try_into().unwrap());
-        let contract_address_salt =
-            Felt252::from_bytes_be_slice(value.contract_address_salt().0.bytes());
-
-        let signature = value
-            .signature()
-            .0
-            .iter()
-            .map(|f| Felt252::from_bytes_be_slice(f.bytes()))
-            .collect();
-        let constructor_calldata = value
-            .constructor_calldata()
-            .0
-            .as_ref()
+        let mut candidates: Vec<u128> = candidates
             .iter()
-            .map(|f| Felt252::from_bytes_be_slice(f.bytes()))
+            .copied()
+            .filter(|max_fee| *max_fee <= balance_ceiling)
             .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
 
-        DeployAccount::new_with_tx_hash(
-            class_hash,
-            // TODO[0.13] Properly convert between V3 tx fields
-            VersionSpecificAccountTxFields::Deprecated(max_fee.0),
-            version,
-            nonce,
-            constructor_calldata,
-            signature,
-            contract_address_salt,
-            tx_hash,
-        )
+        let chain_id = block_context.starknet_os_config.chain_id;
+        let succeeds = |max_fee: u128| -> Result<bool, TransactionError> {
+            let mut probe = DeployAccount {
+                account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(max_fee),
+                ..self.clone()
+            }
+            .with_nonce(self.nonce, chain_id)?;
+            if let Some(sign) = sign {
+                probe.signature = sign(probe.hash_value);
+            }
+            Ok(probe
+                .execute(
+                    &mut state.create_transactional()?,
+                    block_context,
+                    #[cfg(feature = "cairo-native")]
+                    program_cache.clone(),
+                )
+                .is_ok_and(|tx_info| tx_info.revert_error.is_none()))
+        };
+
+        let (mut low, mut high) = (0, candidates.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if succeeds(candidates[mid])? {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(candidates.get(low).copied())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{
-        core::{contract_address::compute_deprecated_class_hash, errors::state_errors::StateError},
-        definitions::block_context::StarknetChainId,
-        services::api::contract_classes::deprecated_contract_class::ContractClass,
-        state::in_memory_state_reader::InMemoryStateReader,
-        state::{cached_state::CachedState, contract_class_cache::PermanentContractClassCache},
-        utils::felt_to_hash,
-    };
-    use std::{path::PathBuf, sync::Arc};
+    /// Runs the checks that can reject the transaction before any execution: the version, chain
+    /// id and nonce checks, the max fee against the minimal fee and the balance, then whether
+    /// the address is still available and the class is declared. Returns the first failure,
+    /// e.g. to filter a mempool. `state` is left untouched.
+    pub fn preflight<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &CachedState<S, C>,
+        block_context: &BlockContext,
+    ) -> Result<(), TransactionError> {
+        let block_context: &BlockContext =
+            &self.block_context_with_recorded_gas_prices(block_context);
+        let mut preflight_state = state.create_transactional()?;
+        self.handle_pre_execution(&mut preflight_state, block_context, None, None)?;
+
+        if !self.address_is_available(&preflight_state)? {
+            return Err(
+                StateError::ContractAddressUnavailable(self.contract_address.clone()).into(),
+            );
+        }
+        if self.options.class_override.is_none() {
+            State::get_contract_class(&mut preflight_state, &self.class_hash)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the selectors of the external entry points the account class exposes, such as
+    /// `__validate__` and `__execute__`, e.g. for account discovery. The class is the override,
+    /// when there's one.
+    pub fn account_selectors<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &CachedState<S, C>,
+    ) -> Result<Vec<Felt252>, TransactionError> {
+        let contract_class = match &self.options.class_override {
+            Some(class_override) => class_override.clone(),
+            None => StateReader::get_contract_class(state, &self.class_hash)?,
+        };
+        Ok(match contract_class {
+            CompiledClass::Deprecated(class) => class
+                .entry_points_by_type
+                .get(&EntryPointType::External)
+                .into_iter()
+                .flatten()
+                .map(|entry_point| *entry_point.selector())
+                .collect(),
+            CompiledClass::Casm { casm: class, .. } => class
+                .entry_points_by_type
+                .external
+                .iter()
+                .map(|entry_point| {
+                    Felt252::from_bytes_be_slice(&entry_point.selector.to_bytes_be())
+                })
+                .collect(),
+        })
+    }
+
+    /// Runs only the validation of the transaction and returns the gas it consumed, e.g. to
+    /// prioritize transactions in a mempool.
+    /// The account is deployed on a transactional copy of `state` and validated right away: the
+    /// constructor isn't run, so the validation sees the account storage uninitialized, and no
+    /// fee is charged. `state` is left untouched.
+    pub fn validate_gas_dry_run<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &CachedState<S, C>,
+        block_context: &BlockContext,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<u128, TransactionError> {
+        let mut dry_run_state = state.create_transactional()?;
+        dry_run_state.deploy_contract(self.contract_address.clone(), self.class_hash)?;
+        let validate_info = self.run_validate_entrypoint(
+            &mut dry_run_state,
+            block_context,
+            &mut ExecutionResourcesManager::default(),
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )?;
+
+        Ok(validate_info
+            .map(|call_info| call_info.gas_consumed)
+            .unwrap_or_default())
+    }
+
+    /// Executes the transaction on a transactional state provided by the caller, which is
+    /// responsible for committing it. This allows several transactions to share a single
+    /// transactional layer.
+    /// The transaction runs on its own layer on top of `txn_state`, merged into it only when the
+    /// transaction succeeds, so a failed transaction leaves `txn_state` untouched. Failures
+    /// aren't reverted: a calculated fee exceeding the max fee is returned as an error.
+    pub fn execute_into<S: StateReader, C: ContractClassCache>(
+        &self,
+        txn_state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.check_deadline()?;
+        Self::check_fee_token_addresses(block_context)?;
+        let block_context: &BlockContext =
+            &self.block_context_with_recorded_gas_prices(block_context);
+        let mut tx_state = txn_state.create_transactional()?;
+        self.handle_pre_execution(&mut tx_state, block_context, None, None)?;
+        self.apply_storage_overlay(&mut tx_state);
+
+        let mut tx_exec_info = self.apply(
+            &mut tx_state,
+            block_context,
+            None,
+            #[cfg(feature = "cairo-native")]
+            program_cache.clone(),
+        )?;
+        Self::check_max_events(&tx_exec_info, block_context)?;
+        self.check_deadline()?;
+        if block_context.increment_nonce_after_execution {
+            self.increment_nonce(&mut tx_state)?;
+        }
+
+        let actual_fee = self.options.fee_rounding.calculate_tx_fee(
+            &tx_exec_info.actual_resources,
+            block_context,
+            &self.fee_type(),
+        )?;
+        let max_fee = self.account_tx_fields.max_fee();
+        if actual_fee > max_fee {
+            if !self.options.report_fee_overage_without_revert {
+                return Err(TransactionError::ActualFeeExceedsMaxFee(
+                    actual_fee, max_fee,
+                ));
+            }
+            tx_exec_info.fee_overage = Some(actual_fee - max_fee);
+        }
+
+        self.handle_fee(
+            &mut tx_state,
+            block_context,
+            &mut tx_exec_info,
+            &self.options.fee_rounding,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )?;
+
+        txn_state.apply_state_update(&StateDiff::from_cached_state(tx_state.cache())?)?;
+        Ok(tx_exec_info)
+    }
+
+    /// Checks the transaction version and chain id, handles the nonce and checks that the account balance
+    /// covers the max fee.
+    fn handle_pre_execution<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        prefetched_nonce: Option<Felt252>,
+        prefetched_balance: Option<(Felt252, Felt252)>,
+    ) -> Result<(), TransactionError> {
+        if let VersionSpecificAccountTxFields::Current(fields) = &self.account_tx_fields {
+            if fields
+                .resource_bounds
+                .l1_gas
+                .as_ref()
+                .map_or(true, |bounds| bounds.max_amount == 0)
+            {
+                return Err(TransactionError::MissingResourceBounds);
+            }
+        }
+
+        if self.version != Felt252::ONE {
+            return Err(TransactionError::UnsupportedTxVersion(
+                "DeployAccount".to_string(),
+                self.version,
+                vec![1],
+            ));
+        }
+
+        if let Some(chain_id) = self.chain_id {
+            let block_chain_id = block_context.starknet_os_config.chain_id;
+            if chain_id != block_chain_id {
+                return Err(TransactionError::ChainIdMismatch(chain_id, block_chain_id));
+            }
+        }
+
+        if block_context.increment_nonce_after_execution {
+            self.check_nonce(state, prefetched_nonce)?;
+        } else {
+            self.handle_nonce(state, prefetched_nonce)?;
+        }
+
+        if !self.skip_fee_transfer {
+            self.check_fee_balance(state, block_context, &self.fee_type(), prefetched_balance)?;
+        }
+        Ok(())
+    }
+
+    /// Builds the reverted execution info of a transaction whose account balance can't cover the
+    /// max fee, and attempts to charge the fee. As the balance is known to be insufficient, a
+    /// failed fee transfer leaves the transaction reverted without any fee charged.
+    fn revert_insufficient_balance<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        error: &TransactionError,
+        fee_strategy: &dyn FeeStrategy,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        let actual_resources = calculate_tx_resources_with_os_resources(
+            ExecutionResourcesManager::default(),
+            &[],
+            TransactionType::DeployAccount,
+            state.count_actual_state_changes(Some((
+                &self.fee_token_address(block_context),
+                &self.contract_address,
+            )))?,
+            None,
+            0,
+            block_context.os_resources(),
+        )
+        .map_err::<TransactionError, _>(|_| TransactionError::ResourcesCalculation)?;
+        let mut tx_exec_info = TransactionExecutionInfo::new_without_fee_info(
+            None,
+            None,
+            Some(error.to_string()),
+            actual_resources,
+            Some(TransactionType::DeployAccount),
+        );
+
+        let mut fee_charge_state = state.create_transactional()?;
+        if self
+            .handle_fee(
+                &mut fee_charge_state,
+                block_context,
+                &mut tx_exec_info,
+                fee_strategy,
+                #[cfg(feature = "cairo-native")]
+                program_cache,
+            )
+            .is_ok()
+        {
+            state.apply_state_update(&StateDiff::from_cached_state(fee_charge_state.cache())?)?;
+        }
+
+        Ok(tx_exec_info)
+    }
+
+    /// Builds the execution info of a transaction that runs neither its constructor nor its
+    /// validation, with the resources of the constant deploy account overhead.
+    fn overhead_execution_info(
+        &self,
+        block_context: &BlockContext,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        let actual_resources = calculate_tx_resources_with_os_resources(
+            ExecutionResourcesManager::default(),
+            &[],
+            TransactionType::DeployAccount,
+            Self::estimated_state_changes(),
+            None,
+            0,
+            block_context.os_resources(),
+        )
+        .map_err::<TransactionError, _>(|_| TransactionError::ResourcesCalculation)?;
+
+        Ok(TransactionExecutionInfo::new_without_fee_info(
+            None,
+            None,
+            None,
+            actual_resources,
+            Some(TransactionType::DeployAccount),
+        ))
+    }
+
+    /// Charges the fee for the resources used by the transaction and sets the fee info.
+    fn handle_fee<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        tx_exec_info: &mut TransactionExecutionInfo,
+        fee_strategy: &dyn FeeStrategy,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<(), TransactionError> {
+        let mut tx_execution_context =
+            self.get_execution_context(self.execute_max_n_steps(block_context));
+        let sequencer_balance_before = self
+            .options
+            .record_sequencer_balance_delta
+            .then(|| self.sequencer_balance(state, block_context))
+            .transpose()?;
+        let (fee_transfer_info, actual_fee) = charge_fee(
+            state,
+            &tx_exec_info.actual_resources,
+            block_context,
+            self.account_tx_fields.max_fee(),
+            &mut tx_execution_context,
+            self.skip_fee_transfer,
+            fee_strategy,
+            &self.fee_type(),
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )?;
+
+        tx_exec_info.set_fee_info(actual_fee, fee_transfer_info);
+        tx_exec_info.fee_paid_in = Some(self.fee_type());
+        if let Some(balance_before) = sequencer_balance_before {
+            tx_exec_info.sequencer_balance_delta =
+                Some(self.sequencer_balance(state, block_context)? - balance_before);
+        }
+        Ok(())
+    }
+
+    /// Reads the sequencer's balance in the fee token this transaction pays with.
+    fn sequencer_balance<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+    ) -> Result<Felt252, TransactionError> {
+        let (low, high) = state.get_fee_token_balance(
+            block_context,
+            &block_context.block_info.sequencer_address,
+            &self.fee_type(),
+        )?;
+        Ok(low + high * Felt252::TWO.pow(128u32))
+    }
+
+    fn constructor_entry_points_empty(
+        &self,
+        contract_class: CompiledClass,
+    ) -> Result<bool, StateError> {
+        Ok(match contract_class {
+            CompiledClass::Deprecated(class) => class
+                .entry_points_by_type
+                .get(&EntryPointType::Constructor)
+                .ok_or(ContractClassError::NoneEntryPointType)?
+                .is_empty(),
+            CompiledClass::Casm { casm: class, .. } => {
+                class.entry_points_by_type.constructor.is_empty()
+            }
+        })
+    }
+
+    /// Returns whether `__validate_deploy__` can be called on the class, either because it
+    /// declares it or because it has a default entry point.
+    fn has_validate_deploy_entry_point(contract_class: &CompiledClass) -> bool {
+        match contract_class {
+            CompiledClass::Deprecated(class) => class
+                .entry_points_by_type
+                .get(&EntryPointType::External)
+                .is_some_and(|entry_points| {
+                    entry_points.iter().any(|entry_point| {
+                        entry_point.selector() == &*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR
+                            || entry_point.selector() == &*DEFAULT_ENTRY_POINT_SELECTOR
+                    })
+                }),
+            CompiledClass::Casm { casm: class, .. } => {
+                let selectors = [
+                    felt_to_biguint(*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR),
+                    felt_to_biguint(*DEFAULT_ENTRY_POINT_SELECTOR),
+                ];
+                class
+                    .entry_points_by_type
+                    .external
+                    .iter()
+                    .any(|entry_point| selectors.contains(&entry_point.selector))
+            }
+        }
+    }
+
+    /// Checks that every builtin the class requires is allowed by the block context, so that a
+    /// class relying on an unavailable builtin is reported as such rather than failing in the
+    /// middle of its validation.
+    fn check_builtins_available(
+        contract_class: &CompiledClass,
+        block_context: &BlockContext,
+    ) -> Result<(), TransactionError> {
+        let Some(allowed_builtins) = block_context.allowed_builtins() else {
+            return Ok(());
+        };
+        let builtin_names: Vec<String> = match contract_class {
+            CompiledClass::Deprecated(class) => class
+                .program()
+                .iter_builtins()
+                .map(|builtin| builtin.name().to_string())
+                .collect(),
+            CompiledClass::Casm { casm: class, .. } => {
+                let entry_points = &class.entry_points_by_type;
+                entry_points
+                    .external
+                    .iter()
+                    .chain(&entry_points.l1_handler)
+                    .chain(&entry_points.constructor)
+                    .flat_map(|entry_point| &entry_point.builtins)
+                    .map(|name| format!("{name}_builtin"))
+                    .collect()
+            }
+        };
+        match builtin_names.into_iter().find(|name| {
+            !Builtin::from_resource_name(name).is_some_and(|b| allowed_builtins.contains(&b))
+        }) {
+            Some(name) => Err(TransactionError::UnavailableBuiltin(name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Execute a call to the cairo-vm using the accounts_validation.cairo contract to validate
+    /// the contract that is being declared. Then it returns the transaction execution info of the run.
+    fn apply<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        on_state_write: Option<&mut dyn FnMut(StateWriteEvent)>,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        // With both the constructor and the validation skipped, only the constant overhead is
+        // left to account for, so there's no need to deploy the contract.
+        if self.skip_execute && self.skip_validate {
+            return self.overhead_execution_info(block_context);
+        }
+
+        // The class to run in place of the one the state resolves for the hash, if any. The
+        // class override takes precedence over the class version of the replayed block.
+        let scoped_class = match (
+            &self.options.class_override,
+            self.options.replay_block_number,
+        ) {
+            (Some(class_override), _) => class_override.clone(),
+            (None, Some(block_number)) => {
+                state.get_contract_class_at(&self.class_hash, block_number)?
+            }
+            (None, None) => {
+                return self.apply_with_class(
+                    state,
+                    block_context,
+                    on_state_write,
+                    #[cfg(feature = "cairo-native")]
+                    program_cache,
+                )
+            }
+        };
+
+        // The class goes into the private class cache, which is looked up first, so that every
+        // entry point execution picks it. The private cache is shared with the state the
+        // transactional one was created from, so the class previously cached for the hash is
+        // restored afterwards, whatever the outcome.
+        let previous_class = state
+            .contract_class_cache_private
+            .write()
+            .map_err(|_| StateError::FailedToReadContractClassCache)?
+            .insert(self.class_hash, scoped_class);
+        let result = self.apply_with_class(
+            state,
+            block_context,
+            on_state_write,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        );
+        let mut private_cache = state
+            .contract_class_cache_private
+            .write()
+            .map_err(|_| StateError::FailedToReadContractClassCache)?;
+        match previous_class {
+            Some(previous_class) => private_cache.insert(self.class_hash, previous_class),
+            None => private_cache.remove(&self.class_hash),
+        };
+        result
+    }
+
+    /// Makes the storage overlay the values read from `state`. They're set as initial values
+    /// rather than writes so that committing `state` doesn't persist them.
+    fn apply_storage_overlay<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+    ) {
+        let cache = state.cache_mut();
+        for ((contract_address, key), value) in &self.options.storage_overlay {
+            let storage_entry = (contract_address.clone(), key.to_bytes_be());
+            // Pending writes would otherwise shadow the overlay. They're still held by the state
+            // the transactional one was created from.
+            cache.storage_writes.remove(&storage_entry);
+            cache.storage_initial_values.insert(storage_entry, *value);
+        }
+    }
+
+    fn apply_with_class<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        mut on_state_write: Option<&mut dyn FnMut(StateWriteEvent)>,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        let contract_class = state.get_contract_class(&self.class_hash)?;
+
+        if let Some(expected_compiled_class_hash) = self.options.expected_compiled_class_hash {
+            let compiled_class_hash =
+                Felt252::from_bytes_be(&State::get_compiled_class_hash(state, &self.class_hash)?.0);
+            if compiled_class_hash != expected_compiled_class_hash {
+                return Err(TransactionError::InvalidCompiledClassHash(
+                    expected_compiled_class_hash.to_string(),
+                    compiled_class_hash.to_string(),
+                ));
+            }
+        }
+
+        if !self.skip_validate
+            && self.options.validate_result_override.is_none()
+            && !Self::has_validate_deploy_entry_point(&contract_class)
+        {
+            return Err(TransactionError::NotAnAccountClass(self.class_hash));
+        }
+        Self::check_builtins_available(&contract_class, block_context)?;
+
+        if self.options.validate_without_deploy {
+            return self.validate_with_temporary_binding(
+                state,
+                block_context,
+                #[cfg(feature = "cairo-native")]
+                program_cache,
+            );
+        }
+
+        state.deploy_contract(self.contract_address.clone(), self.class_hash)?;
+        if let Some(on_state_write) = on_state_write.as_mut() {
+            on_state_write(StateWriteEvent::Deploy {
+                contract_address: self.contract_address.clone(),
+                class_hash: self.class_hash,
+            });
+        }
+        // Snapshot of the writes made so far, to tell which ones the constructor and the
+        // validation made.
+        let writes_before_constructor =
+            (on_state_write.is_some() || self.options.record_storage_access_counts).then(|| {
+                (
+                    state.cache().storage_writes.clone(),
+                    state.cache().nonce_writes.clone(),
+                )
+            });
+
+        // Failures of the constructor and of the validation are tagged with their phase, so
+        // callers can tell them apart.
+        let mut resources_manager = ExecutionResourcesManager::default();
+        #[cfg(feature = "timings")]
+        let constructor_start = Instant::now();
+        let constructor_call_info = self
+            .handle_constructor(
+                contract_class,
+                state,
+                block_context,
+                &mut resources_manager,
+                #[cfg(feature = "cairo-native")]
+                program_cache.clone(),
+            )
+            .map_err(|e| TransactionError::ConstructorFailed(Box::new(e)))?;
+        #[cfg(feature = "timings")]
+        let constructor_duration = constructor_start.elapsed();
+        self.check_deadline()?;
+
+        if let (Some(on_state_write), Some((storage_writes, nonce_writes))) =
+            (on_state_write, writes_before_constructor.as_ref())
+        {
+            report_state_writes(state.cache(), storage_writes, nonce_writes, on_state_write);
+        }
+        let post_constructor_state_diff = self
+            .options
+            .record_post_constructor_state_diff
+            .then(|| StateDiff::from_cached_state(state.cache()))
+            .transpose()?;
+
+        #[cfg(feature = "timings")]
+        let validate_start = Instant::now();
+        let validate_info = if self.skip_validate {
+            None
+        } else {
+            self.run_validate_entrypoint(
+                state,
+                block_context,
+                &mut resources_manager,
+                #[cfg(feature = "cairo-native")]
+                program_cache,
+            )
+            .map_err(|e| TransactionError::ValidationFailed(Box::new(e)))?
+        };
+        #[cfg(feature = "timings")]
+        let validate_duration = (!self.skip_validate).then(|| validate_start.elapsed());
+
+        let syscall_gas = self
+            .options
+            .record_syscall_gas
+            .then(|| resources_manager.syscall_gas());
+        let actual_resources = calculate_tx_resources_with_os_resources(
+            resources_manager,
+            &[Some(constructor_call_info.clone()), validate_info.clone()],
+            TransactionType::DeployAccount,
+            state.count_actual_state_changes(Some((
+                &self.fee_token_address(block_context),
+                &self.contract_address,
+            )))?,
+            None,
+            0,
+            block_context.os_resources(),
+        )
+        .map_err::<TransactionError, _>(|_| TransactionError::ResourcesCalculation)?;
+
+        let mut tx_info = TransactionExecutionInfo::new_without_fee_info(
+            validate_info,
+            Some(constructor_call_info),
+            None,
+            actual_resources,
+            Some(TransactionType::DeployAccount),
+        );
+        if let Some((storage_writes, _)) =
+            writes_before_constructor.filter(|_| self.options.record_storage_access_counts)
+        {
+            tx_info.storage_access_counts = Some(StorageAccessCounts {
+                reads: tx_info
+                    .iter_calls()
+                    .map(|call| call.storage_read_values.len())
+                    .sum(),
+                writes: state
+                    .cache()
+                    .storage_writes
+                    .iter()
+                    .filter(|(entry, value)| storage_writes.get(*entry) != Some(*value))
+                    .count(),
+            });
+        }
+        tx_info.post_constructor_state_diff = post_constructor_state_diff;
+        tx_info.syscall_gas = syscall_gas;
+        #[cfg(feature = "timings")]
+        let tx_info = TransactionExecutionInfo {
+            phase_timings: PhaseTimings {
+                validate: validate_duration,
+                constructor: Some(constructor_duration),
+            },
+            ..tx_info
+        };
+        Ok(tx_info)
+    }
+
+    /// Runs the validation against a copy of `state` where the class is bound to the contract
+    /// address, which is discarded afterwards, see [Self::validate_without_deploy].
+    fn validate_with_temporary_binding<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        let mut binding_state = state.create_transactional()?;
+        binding_state.deploy_contract(self.contract_address.clone(), self.class_hash)?;
+
+        let mut resources_manager = ExecutionResourcesManager::default();
+        let validate_info = if self.skip_validate {
+            None
+        } else {
+            self.run_validate_entrypoint(
+                &mut binding_state,
+                block_context,
+                &mut resources_manager,
+                #[cfg(feature = "cairo-native")]
+                program_cache,
+            )
+            .map_err(|e| TransactionError::ValidationFailed(Box::new(e)))?
+        };
+
+        let actual_resources = calculate_tx_resources_with_os_resources(
+            resources_manager,
+            &[validate_info.clone()],
+            TransactionType::DeployAccount,
+            state.count_actual_state_changes(Some((
+                &self.fee_token_address(block_context),
+                &self.contract_address,
+            )))?,
+            None,
+            0,
+            block_context.os_resources(),
+        )
+        .map_err::<TransactionError, _>(|_| TransactionError::ResourcesCalculation)?;
+
+        Ok(TransactionExecutionInfo::new_without_fee_info(
+            validate_info,
+            None,
+            None,
+            actual_resources,
+            Some(TransactionType::DeployAccount),
+        ))
+    }
+
+    /// Handles the constructor of a contract, executes it if necessary.
+    pub fn handle_constructor<S: StateReader, C: ContractClassCache>(
+        &self,
+        contract_class: CompiledClass,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        resources_manager: &mut ExecutionResourcesManager,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<CallInfo, TransactionError> {
+        if self.constructor_entry_points_empty(contract_class)? {
+            if !self.constructor_calldata.is_empty() {
+                return Err(TransactionError::EmptyConstructorCalldata);
+            }
+
+            Ok(CallInfo::empty_constructor_call(
+                self.contract_address.clone(),
+                Address(Felt252::ZERO),
+                Some(self.class_hash),
+            ))
+        } else {
+            self.run_constructor_entrypoint(
+                state,
+                block_context,
+                resources_manager,
+                #[cfg(feature = "cairo-native")]
+                program_cache,
+            )
+        }
+    }
+
+    /// Handles the nonce of a transaction, verifies if it is valid and increments it.
+    /// If `prefetched_nonce` is set, it is used as the current nonce instead of reading it from the state.
+    fn handle_nonce<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        prefetched_nonce: Option<Felt252>,
+    ) -> Result<(), TransactionError> {
+        if self.version.is_zero() {
+            return Ok(());
+        }
+
+        let current_nonce = self.check_nonce(state, prefetched_nonce)?;
+        let next_nonce = self.next_nonce(current_nonce);
+        state
+            .cache_mut()
+            .nonce_writes_mut()
+            .insert(self.contract_address.clone(), next_nonce);
+        Ok(())
+    }
+
+    /// Increments the nonce of the account once the transaction has been executed, for block
+    /// contexts that increment it after execution. The nonce must have been checked beforehand.
+    fn increment_nonce<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+    ) -> Result<(), TransactionError> {
+        if self.version.is_zero() {
+            return Ok(());
+        }
+
+        let current_nonce = state.get_nonce_at(&self.contract_address)?;
+        let next_nonce = self.next_nonce(current_nonce);
+        state
+            .cache_mut()
+            .nonce_writes_mut()
+            .insert(self.contract_address.clone(), next_nonce);
+        Ok(())
+    }
+
+    /// Returns the nonce of the account after this transaction, given its `current_nonce`.
+    /// A transaction nonce ahead of the current one, as allowed by
+    /// [DeployAccountOptions::max_nonce_gap], moves the account's nonce past it.
+    fn next_nonce(&self, current_nonce: Felt252) -> Felt252 {
+        if self.nonce_in_allowed_range(current_nonce) {
+            self.nonce + Felt252::ONE
+        } else {
+            current_nonce + Felt252::ONE
+        }
+    }
+
+    /// Returns whether the transaction nonce is the account's `current_nonce`, or ahead of it by
+    /// at most [DeployAccountOptions::max_nonce_gap].
+    fn nonce_in_allowed_range(&self, current_nonce: Felt252) -> bool {
+        self.nonce >= current_nonce
+            && self.nonce - current_nonce <= Felt252::from(self.options.max_nonce_gap)
+    }
+
+    /// Verifies that the nonce of a transaction is valid and returns the current nonce of the account.
+    /// If `prefetched_nonce` is set, it is used as the current nonce instead of reading it from the state.
+    fn check_nonce<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        prefetched_nonce: Option<Felt252>,
+    ) -> Result<Felt252, TransactionError> {
+        let current_nonce = match prefetched_nonce {
+            Some(nonce) => {
+                // Record the supplied nonce as the initial value so that neither the nonce check
+                // nor the state changes count go back to the state reader for it.
+                state
+                    .cache_mut()
+                    .nonce_initial_values_mut()
+                    .entry(self.contract_address.clone())
+                    .or_insert(nonce);
+                nonce
+            }
+            // In blockifier, get_nonce_at returns zero if no entry is found.
+            None => state.get_nonce_at(&self.contract_address)?,
+        };
+        if !self.nonce_in_allowed_range(current_nonce) && !self.skip_nonce_check {
+            return Err(TransactionError::InvalidTransactionNonce(
+                current_nonce.to_string(),
+                self.nonce.to_string(),
+            ));
+        }
+        Ok(current_nonce)
+    }
+
+    /// Checks that the max fee covers the minimal fee and that the account's fee token balance
+    /// covers the max fee. If `prefetched_balance` is set, it is used instead of reading the
+    /// balance from the state.
+    /// The balance is read through `state`, so when it's a transactional state shared by a batch
+    /// the deductions of earlier, uncommitted transactions are taken into account.
+    fn check_fee_balance<S: State + StateReader>(
+        &self,
+        state: &mut S,
+        block_context: &BlockContext,
+        fee_type: &FeeType,
+        prefetched_balance: Option<(Felt252, Felt252)>,
+    ) -> Result<(), TransactionError> {
+        if self.has_zero_fee_bounds() {
+            return Ok(());
+        }
+        // The class is only needed to refine the estimate; a missing class is reported when the
+        // contract is deployed. The deployed class is the override, when there's one.
+        let contract_class = match &self.options.class_override {
+            _ if !self.options.constructor_aware_fee_estimate => None,
+            Some(class_override) => Some(class_override.clone()),
+            None => State::get_contract_class(state, &self.class_hash).ok(),
+        };
+        let minimal_fee =
+            self.estimate_minimal_fee_for_class(block_context, contract_class.as_ref())?;
+        // Check max fee is at least the estimated constant overhead.
+        if self.account_tx_fields.max_fee() < minimal_fee {
+            return Err(TransactionError::MaxFeeTooLow(
+                self.account_tx_fields.max_fee(),
+                minimal_fee,
+            ));
+        }
+        // Check that the current balance is high enough to cover the max_fee
+        let (balance_low, balance_high) = match prefetched_balance {
+            Some(balance) => balance,
+            None => {
+                state.get_fee_token_balance(block_context, self.contract_address(), fee_type)?
+            }
+        };
+        // The fee is at most 128 bits, while balance is 256 bits (split into two 128 bit words).
+        if balance_high.is_zero() && balance_low < Felt252::from(self.account_tx_fields.max_fee()) {
+            return Err(TransactionError::MaxFeeExceedsBalance(
+                self.account_tx_fields.max_fee(),
+                balance_low,
+                balance_high,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Estimates the L1 gas used to publish the onchain data of a deploy account transaction.
+    pub fn estimate_data_gas(&self) -> u128 {
+        let onchain_data_length = get_onchain_data_segment_length(&Self::estimated_state_changes());
+        (onchain_data_length * SHARP_GAS_PER_MEMORY_WORD) as u128
+    }
+
+    /// The state changes of a deploy account transaction: the deployed contract's class hash and
+    /// the fee token balance update.
+    fn estimated_state_changes() -> StateChangesCount {
+        StateChangesCount {
+            n_storage_updates: 1,
+            n_class_hash_updates: 1,
+            n_compiled_class_hash_updates: 0,
+            n_modified_contracts: 1,
+        }
+    }
+
+    /// The state changes of a deploy account of `contract_class`. A class with a constructor is
+    /// expected to initialize at least one storage slot on top of [Self::estimated_state_changes].
+    /// The deployed contract is modified either way, since its class hash is set.
+    fn estimated_state_changes_for_class(contract_class: &CompiledClass) -> StateChangesCount {
+        let has_constructor = match contract_class {
+            CompiledClass::Deprecated(class) => class
+                .entry_points_by_type
+                .get(&EntryPointType::Constructor)
+                .is_some_and(|entry_points| !entry_points.is_empty()),
+            CompiledClass::Casm { casm: class, .. } => {
+                !class.entry_points_by_type.constructor.is_empty()
+            }
+        };
+        let mut state_changes = Self::estimated_state_changes();
+        if has_constructor {
+            state_changes.n_storage_updates += 1;
+        }
+        state_changes
+    }
+
+    /// Estimates the number of Cairo steps run by a deploy account transaction, as given by the
+    /// block context's OS resources table.
+    pub fn estimate_compute_steps(&self, block_context: &BlockContext) -> u64 {
+        block_context
+            .os_resources()
+            .tx_resources(&TransactionType::DeployAccount)
+            .map_or(ESTIMATED_DEPLOY_ACCOUNT_STEPS, |resources| {
+                resources.n_steps
+            }) as u64
+    }
+
+    /// Estimates the fee of the constant overhead of the transaction, priced in the token it
+    /// pays with. A zero gas price yields a zero minimal fee.
+    /// This is the minimal fee the max fee is checked against, and reported by
+    /// [TransactionError::MaxFeeTooLow], unless `constructor_aware_fee_estimate` is set.
+    pub fn estimate_minimal_fee(
+        &self,
+        block_context: &BlockContext,
+    ) -> Result<u128, TransactionError> {
+        self.estimate_minimal_fee_for_class(block_context, None)
+    }
+
+    /// Like [Self::estimate_minimal_fee], accounting for the storage initialized by the
+    /// constructor of `contract_class` when it's known.
+    fn estimate_minimal_fee_for_class(
+        &self,
+        block_context: &BlockContext,
+        contract_class: Option<&CompiledClass>,
+    ) -> Result<u128, TransactionError> {
+        let data_gas = match contract_class {
+            Some(contract_class) => {
+                let onchain_data_length = get_onchain_data_segment_length(
+                    &Self::estimated_state_changes_for_class(contract_class),
+                );
+                (onchain_data_length * SHARP_GAS_PER_MEMORY_WORD) as u128
+            }
+            None => self.estimate_data_gas(),
+        };
+        let resources = HashMap::from([
+            ("l1_gas_usage".to_string(), data_gas as usize),
+            (
+                "n_steps".to_string(),
+                self.estimate_compute_steps(block_context) as usize,
+            ),
+        ]);
+        self.options
+            .fee_rounding
+            .calculate_tx_fee(&resources, block_context, &self.fee_type())
+    }
+
+    pub fn run_constructor_entrypoint<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        resources_manager: &mut ExecutionResourcesManager,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<CallInfo, TransactionError> {
+        let entry_point = ExecutionEntryPoint::new(
+            self.contract_address.clone(),
+            self.constructor_calldata.clone(),
+            *CONSTRUCTOR_ENTRY_POINT_SELECTOR,
+            self.options
+                .constructor_caller_override
+                .clone()
+                .unwrap_or(Address(Felt252::ZERO)),
+            EntryPointType::Constructor,
+            None,
+            None,
+            INITIAL_GAS_COST,
+        );
+
+        let ExecutionResult { call_info, .. } = if self.skip_execute {
+            ExecutionResult::default()
+        } else {
+            entry_point.execute(
+                state,
+                block_context,
+                resources_manager,
+                &mut self.get_execution_context(self.constructor_max_n_steps(block_context)),
+                false,
+                self.constructor_max_n_steps(block_context),
+                #[cfg(feature = "cairo-native")]
+                program_cache,
+            )?
+        };
+
+        // The account isn't fully deployed until its constructor returns, so calling back into
+        // it has no well-defined semantics. Library calls run in the account's own context and
+        // are allowed.
+        if let Some(call_info) = &call_info {
+            if call_info
+                .internal_calls
+                .iter()
+                .flat_map(CallInfo::gen_call_topology)
+                .any(|call| {
+                    call.call_type == Some(CallType::Call)
+                        && call.contract_address == self.contract_address
+                })
+            {
+                return Err(TransactionError::ConstructorSelfCall(
+                    self.contract_address.clone(),
+                ));
+            }
+        }
+
+        let call_info = verify_no_calls_to_other_contracts(&call_info)
+            .map_err(|err| TransactionError::InvalidContractCall(Box::new(err)))?;
+        Ok(call_info)
+    }
+
+    /// Fails with [TransactionError::Timeout] if [Self::deadline] has been reached.
+    fn check_deadline(&self) -> Result<(), TransactionError> {
+        match self.options.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(TransactionError::Timeout),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails with [TransactionError::AmbiguousFeeTokens] if the block context rejects
+    /// ambiguous fee tokens and both fee tokens share an address.
+    fn check_fee_token_addresses(block_context: &BlockContext) -> Result<(), TransactionError> {
+        let fee_token_addresses = &block_context.starknet_os_config.fee_token_address;
+        if block_context.reject_ambiguous_fee_tokens
+            && fee_token_addresses.eth_fee_token_address
+                == fee_token_addresses.strk_fee_token_address
+        {
+            return Err(TransactionError::AmbiguousFeeTokens(
+                fee_token_addresses.eth_fee_token_address.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fails with [TransactionError::TooManyEvents] when the calls of `tx_info` emitted more
+    /// events than [BlockContext::max_events] allows.
+    fn check_max_events(
+        tx_info: &TransactionExecutionInfo,
+        block_context: &BlockContext,
+    ) -> Result<(), TransactionError> {
+        if let Some(max_events) = block_context.max_events() {
+            let n_events = tx_info.iter_calls().map(|call| call.events.len()).sum();
+            if n_events > max_events {
+                return Err(TransactionError::TooManyEvents(n_events, max_events));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the block context to execute the transaction under, with its gas prices replaced
+    /// by [Self::recorded_gas_prices] when they are set.
+    fn block_context_with_recorded_gas_prices<'a>(
+        &self,
+        block_context: &'a BlockContext,
+    ) -> Cow<'a, BlockContext> {
+        match &self.options.recorded_gas_prices {
+            Some(gas_prices) => {
+                let mut block_context = block_context.clone();
+                block_context.starknet_os_config.gas_price = gas_prices.clone();
+                Cow::Owned(block_context)
+            }
+            None => Cow::Borrowed(block_context),
+        }
+    }
+
+    /// Returns the step limit of the validation, honouring [Self::max_n_steps_override].
+    fn validate_max_n_steps(&self, block_context: &BlockContext) -> u64 {
+        self.options
+            .max_n_steps_override
+            .map_or(block_context.validate_max_n_steps, |(validate, _)| validate)
+    }
+
+    /// Returns the step limit of the constructor, which runs under the validation limit unless
+    /// [Self::max_n_steps_override] is set.
+    fn constructor_max_n_steps(&self, block_context: &BlockContext) -> u64 {
+        self.options
+            .max_n_steps_override
+            .map_or(block_context.validate_max_n_steps, |(_, execute)| execute)
+    }
+
+    /// Returns the step limit of the fee transfer, honouring [Self::max_n_steps_override].
+    fn execute_max_n_steps(&self, block_context: &BlockContext) -> u64 {
+        self.options
+            .max_n_steps_override
+            .map_or(block_context.invoke_tx_max_n_steps, |(_, execute)| execute)
+    }
+
+    pub fn get_execution_context(&self, n_steps: u64) -> TransactionExecutionContext {
+        TransactionExecutionContext::new(
+            self.contract_address.clone(),
+            self.hash_value,
+            self.signature.clone(),
+            self.account_tx_fields.clone(),
+            self.nonce,
+            n_steps,
+            self.version,
+        )
+    }
+
+    /// Builds the `__validate_deploy__` call executed by [`Self::run_validate_entrypoint`].
+    /// Its calldata is the class hash and salt followed by the constructor calldata.
+    pub fn build_validate_entry_point(&self) -> ExecutionEntryPoint {
+        ExecutionEntryPoint::new(
+            self.contract_address.clone(),
+            [
+                Felt252::from_bytes_be(&self.class_hash.0),
+                self.contract_address_salt,
+            ]
+            .into_iter()
+            .chain(self.constructor_calldata.iter().cloned())
+            .collect(),
+            *VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR,
+            Address(Felt252::ZERO),
+            EntryPointType::External,
+            None,
+            None,
+            INITIAL_GAS_COST,
+        )
+    }
+
+    pub fn run_validate_entrypoint<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        resources_manager: &mut ExecutionResourcesManager,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<Option<CallInfo>, TransactionError> {
+        let call = self.build_validate_entry_point();
+
+        let ExecutionResult { call_info, .. } = if self.skip_execute {
+            ExecutionResult::default()
+        } else if let Some(validate_result) = &self.options.validate_result_override {
+            ExecutionResult {
+                call_info: Some(validate_result.clone()),
+                ..Default::default()
+            }
+        } else {
+            call.execute(
+                state,
+                block_context,
+                resources_manager,
+                &mut self.get_execution_context(self.validate_max_n_steps(block_context)),
+                false,
+                self.validate_max_n_steps(block_context),
+                #[cfg(feature = "cairo-native")]
+                program_cache,
+            )?
+        };
+
+        // Validate the return data
+        let class_hash = state.get_class_hash_at(&self.contract_address)?;
+        let contract_class = state
+            .get_contract_class(&class_hash)
+            .map_err(|_| TransactionError::MissingCompiledClass)?;
+        if matches!(
+            contract_class,
+            CompiledClass::Casm {
+                sierra: Some(_),
+                ..
+            }
+        ) {
+            // The account contract class is a Cairo 1.0 contract; the `validate` entry point should
+            // return `VALID`.
+            if !call_info
+                .as_ref()
+                .map(|ci| ci.retdata == vec![*VALIDATE_RETDATA])
+                .unwrap_or_default()
+            {
+                return Err(TransactionError::WrongValidateRetdata);
+            }
+        }
+
+        verify_no_calls_to_other_contracts(&call_info)
+            .map_err(|err| TransactionError::InvalidContractCall(Box::new(err)))?;
+
+        Ok(call_info)
+    }
+
+    pub fn create_for_simulation(
+        &self,
+        skip_validate: bool,
+        skip_execute: bool,
+        skip_fee_transfer: bool,
+        ignore_max_fee: bool,
+        skip_nonce_check: bool,
+    ) -> Transaction {
+        Transaction::DeployAccount(self.with_skip_flags(SkipFlags {
+            skip_validate,
+            skip_execute,
+            skip_fee_transfer,
+            ignore_max_fee,
+            skip_nonce_check,
+        }))
+    }
+
+    /// Returns a copy of the transaction with the given skip flags, like
+    /// [Self::create_for_simulation] but without wrapping it in a [Transaction].
+    pub fn with_skip_flags(&self, skip_flags: SkipFlags) -> Self {
+        DeployAccount {
+            skip_validate: skip_flags.skip_validate,
+            skip_execute: skip_flags.skip_execute,
+            skip_fee_transfer: skip_flags.skip_fee_transfer,
+            account_tx_fields: if skip_flags.ignore_max_fee {
+                self.account_tx_fields_without_max_fee()
+            } else {
+                self.account_tx_fields.clone()
+            },
+            skip_nonce_check: skip_flags.skip_nonce_check,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the account tx fields of this transaction with the max fee lifted.
+    fn account_tx_fields_without_max_fee(&self) -> VersionSpecificAccountTxFields {
+        if let VersionSpecificAccountTxFields::Current(current) = &self.account_tx_fields {
+            let mut current_fields = current.clone();
+            current_fields.resource_bounds.l1_gas = Some(ResourceBounds {
+                max_amount: u64::MAX,
+                max_price_per_unit: u128::MAX,
+            });
+            VersionSpecificAccountTxFields::Current(current_fields)
+        } else {
+            VersionSpecificAccountTxFields::new_deprecated(u128::MAX)
+        }
+    }
+
+    /// Estimates the fee of the transaction with the semantics of the RPC `estimate_fee`: the
+    /// max fee and the account balance are ignored, and any failure of the validation or of the
+    /// constructor is returned as an error rather than as a reverted execution.
+    /// The transaction runs on a transactional copy of `state`, which is left untouched.
+    pub fn estimate<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &CachedState<S, C>,
+        block_context: &BlockContext,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<u128, TransactionError> {
+        let estimated_tx = DeployAccount {
+            skip_fee_transfer: true,
+            account_tx_fields: self.account_tx_fields_without_max_fee(),
+            ..self.clone()
+        };
+        let tx_info = estimated_tx.execute(
+            &mut state.create_transactional()?,
+            block_context,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )?;
+        if let Some(revert_error) = tx_info.revert_error {
+            return Err(TransactionError::TransactionReverted(revert_error));
+        }
+
+        Ok(tx_info.actual_fee)
+    }
+
+    /// Estimates the fee of the transaction under both the V1 (ETH) and the V3 (STRK) fee
+    /// regimes, with the semantics of [Self::estimate], e.g. to advise on migrating to V3.
+    /// V3 deploys can't be executed yet, so the V3 fee is estimated by charging the transaction
+    /// in STRK, and the V3 resource bounds are derived from it.
+    pub fn fee_comparison<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &CachedState<S, C>,
+        block_context: &BlockContext,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<FeeComparison, TransactionError> {
+        let estimate_in = |fee_type: FeeType| {
+            let mut deploy = self.clone();
+            deploy.options.fee_type_override = Some(fee_type);
+            deploy.estimate(
+                state,
+                block_context,
+                #[cfg(feature = "cairo-native")]
+                program_cache.clone(),
+            )
+        };
+        let v1_fee = estimate_in(FeeType::Eth)?;
+        let v3_fee = estimate_in(FeeType::Strk)?;
+
+        let strk_l1_gas_price = self
+            .block_context_with_recorded_gas_prices(block_context)
+            .starknet_os_config
+            .gas_price
+            .strk_l1_gas_price;
+        Ok(FeeComparison {
+            v1_fee,
+            v3_fee,
+            v3_l1_resource_bounds: ResourceBounds::from_eth_max_fee(v3_fee, strk_l1_gas_price, 0),
+        })
+    }
+
+    /// Converts a starknet_api deploy account transaction.
+    /// If `strict` is set, a contract address salt that doesn't fit in the field is rejected
+    /// instead of being reduced modulo the field prime.
+    pub fn from_sn_api_transaction(
+        value: starknet_api::transaction::DeployAccountTransaction,
+        tx_hash: Felt252,
+        strict: bool,
+    ) -> Result<Self, TransactionError> {
+        let max_fee = match value {
+            starknet_api::transaction::DeployAccountTransaction::V1(ref tx) => tx.max_fee,
+            starknet_api::transaction::DeployAccountTransaction::V3(_) => {
+                return Err(TransactionError::UnsuportedV3Transaction)
+            }
+        };
+        let version = Felt252::from_bytes_be_slice(value.version().0.bytes());
+        let nonce = Felt252::from_bytes_be_slice(value.nonce().0.bytes());
+        let class_hash: ClassHash = ClassHash(value.class_hash().0.bytes().try_into().unwrap());
+        let salt_bytes = value.contract_address_salt().0.bytes();
+        let contract_address_salt = Felt252::from_bytes_be_slice(salt_bytes);
+        if strict && contract_address_salt.to_bytes_be().as_slice() != salt_bytes {
+            return Err(TransactionError::SaltOutOfRange(format!(
+                "0x{}",
+                hex::encode(salt_bytes)
+            )));
+        }
+
+        let signature = value
+            .signature()
+            .0
+            .iter()
+            .map(|f| Felt252::from_bytes_be_slice(f.bytes()))
+            .collect();
+        let constructor_calldata = value
+            .constructor_calldata()
+            .0
+            .as_ref()
+            .iter()
+            .map(|f| Felt252::from_bytes_be_slice(f.bytes()))
+            .collect();
+
+        DeployAccount::new_with_tx_hash(
+            class_hash,
+            // TODO[0.13] Properly convert between V3 tx fields
+            VersionSpecificAccountTxFields::Deprecated(max_fee.0),
+            version,
+            nonce,
+            constructor_calldata,
+            signature,
+            contract_address_salt,
+            tx_hash,
+        )
+    }
+
+    /// Builds a deploy account transaction from its JSON-RPC `BROADCASTED_DEPLOY_ACCOUNT_TXN`
+    /// representation, computing its hash for `chain_id`.
+    /// Only V1 payloads are supported: V3 transactions are hashed with Poseidon over their
+    /// resource bounds, which isn't implemented yet, so they are rejected instead of being given
+    /// a wrong hash.
+    pub fn from_broadcasted_json(
+        value: serde_json::Value,
+        chain_id: Felt252,
+    ) -> Result<Self, TransactionError> {
+        let version = json_felt(&value, "version")?;
+        if get_tx_version(version) != Felt252::ONE {
+            return Err(TransactionError::UnsupportedTxVersion(
+                "DeployAccount".to_string(),
+                version,
+                vec![1],
+            ));
+        }
+        let account_tx_fields =
+            VersionSpecificAccountTxFields::Deprecated(json_hex_u128(&value, "max_fee")?);
+
+        DeployAccount::new(
+            ClassHash::from(json_felt(&value, "class_hash")?),
+            account_tx_fields,
+            version,
+            json_felt(&value, "nonce")?,
+            json_felt_array(&value, "constructor_calldata")?,
+            json_felt_array(&value, "signature")?,
+            json_felt(&value, "contract_address_salt")?,
+            chain_id,
+        )
+    }
+}
+
+/// Reports the storage and nonce writes in `cache` that differ from the given earlier writes,
+/// ordered by contract address and storage key.
+fn report_state_writes(
+    cache: &StateCache,
+    storage_writes_before: &HashMap<StorageEntry, Felt252>,
+    nonce_writes_before: &HashMap<Address, Felt252>,
+    on_state_write: &mut dyn FnMut(StateWriteEvent),
+) {
+    let mut storage_writes: Vec<_> = cache
+        .storage_writes
+        .iter()
+        .filter(|(entry, value)| storage_writes_before.get(*entry) != Some(*value))
+        .collect();
+    storage_writes.sort_by_key(|((address, key), _)| (address.0, *key));
+    for ((contract_address, key), value) in storage_writes {
+        on_state_write(StateWriteEvent::StorageWrite {
+            contract_address: contract_address.clone(),
+            key: *key,
+            value: *value,
+        });
+    }
+
+    let mut nonce_writes: Vec<_> = cache
+        .nonce_writes
+        .iter()
+        .filter(|(address, nonce)| nonce_writes_before.get(*address) != Some(*nonce))
+        .collect();
+    nonce_writes.sort_by_key(|(address, _)| address.0);
+    for (contract_address, nonce) in nonce_writes {
+        on_state_write(StateWriteEvent::NonceUpdate {
+            contract_address: contract_address.clone(),
+            nonce: *nonce,
+        });
+    }
+}
+
+fn json_field<'a>(
+    value: &'a serde_json::Value,
+    field: &str,
+) -> Result<&'a serde_json::Value, TransactionError> {
+    value
+        .get(field)
+        .ok_or_else(|| TransactionError::InvalidBroadcastedTxField(field.to_string()))
+}
+
+fn json_felt(value: &serde_json::Value, field: &str) -> Result<Felt252, TransactionError> {
+    json_field(value, field)?
+        .as_str()
+        .and_then(|felt| Felt252::from_hex(felt).ok())
+        .ok_or_else(|| TransactionError::InvalidBroadcastedTxField(field.to_string()))
+}
+
+fn json_felt_array(
+    value: &serde_json::Value,
+    field: &str,
+) -> Result<Vec<Felt252>, TransactionError> {
+    json_field(value, field)?
+        .as_array()
+        .and_then(|felts| {
+            felts
+                .iter()
+                .map(|felt| felt.as_str().and_then(|felt| Felt252::from_hex(felt).ok()))
+                .collect()
+        })
+        .ok_or_else(|| TransactionError::InvalidBroadcastedTxField(field.to_string()))
+}
+
+fn json_hex_u128(value: &serde_json::Value, field: &str) -> Result<u128, TransactionError> {
+    json_field(value, field)?
+        .as_str()
+        .and_then(|hex| u128::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| TransactionError::InvalidBroadcastedTxField(field.to_string()))
+}
+
+impl From<DeployAccount> for Transaction {
+    /// Wraps a [DeployAccount] into a [Transaction] with all the skip flags disabled.
+    fn from(tx: DeployAccount) -> Self {
+        Transaction::DeployAccount(DeployAccount {
+            skip_validate: false,
+            skip_execute: false,
+            skip_fee_transfer: false,
+            skip_nonce_check: false,
+            ..tx
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{contract_address::compute_deprecated_class_hash, errors::state_errors::StateError},
+        definitions::{
+            block_context::{FeeTokenAddresses, GasPrices, StarknetChainId},
+            constants::{
+                DEFAULT_VALIDATE_MAX_N_STEPS, EXECUTE_ENTRY_POINT_SELECTOR,
+                VALIDATE_DECLARE_ENTRY_POINT_SELECTOR, VALIDATE_ENTRY_POINT_SELECTOR,
+            },
+        },
+        execution::{Builtin, ExecSummary, ExecutionStatus, FunctionInvocation, Syscall},
+        services::api::contract_classes::deprecated_contract_class::ContractClass,
+        state::in_memory_state_reader::InMemoryStateReader,
+        state::state_cache::{StateCache, StorageEntry},
+        state::{
+            cached_state::CachedState,
+            contract_class_cache::{PermanentContractClassCache, VersionedContractClassCache},
+        },
+        syscalls::business_logic_syscall_handler::SYSCALL_GAS_COST,
+        transaction::{fee::calculate_tx_fee, CurrentAccountTxFields, DataAvailabilityMode},
+        utils::{
+            calculate_sn_keccak, calculate_tx_resources, felt_to_hash, get_storage_var_address,
+            CompiledClassHash,
+        },
+    };
+    use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+    use starknet_crypto::{get_public_key, rfc6979_generate_k, sign};
+    use std::{
+        collections::HashSet,
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    /// A [StateReader] that counts how many times nonces and storage values were requested.
+    #[derive(Default)]
+    struct CountingStateReader {
+        inner: InMemoryStateReader,
+        nonce_reads: AtomicUsize,
+        storage_reads: AtomicUsize,
+    }
+
+    impl StateReader for CountingStateReader {
+        fn get_contract_class(&self, class_hash: &ClassHash) -> Result<CompiledClass, StateError> {
+            self.inner.get_contract_class(class_hash)
+        }
+
+        fn get_class_hash_at(&self, contract_address: &Address) -> Result<ClassHash, StateError> {
+            self.inner.get_class_hash_at(contract_address)
+        }
+
+        fn get_nonce_at(&self, contract_address: &Address) -> Result<Felt252, StateError> {
+            self.nonce_reads.fetch_add(1, Ordering::SeqCst);
+            self.inner.get_nonce_at(contract_address)
+        }
+
+        fn get_storage_at(&self, storage_entry: &StorageEntry) -> Result<Felt252, StateError> {
+            self.storage_reads.fetch_add(1, Ordering::SeqCst);
+            self.inner.get_storage_at(storage_entry)
+        }
+
+        fn get_compiled_class_hash(
+            &self,
+            class_hash: &ClassHash,
+        ) -> Result<CompiledClassHash, StateError> {
+            self.inner.get_compiled_class_hash(class_hash)
+        }
+    }
+
+    /// Loads the deprecated contract class at `path`, sets it in a new state and builds a
+    /// [DeployAccount] of the given version for it, with no constructor calldata.
+    fn deploy_account_fixture(
+        path: &str,
+        version: Felt252,
+    ) -> (
+        CachedState<InMemoryStateReader, PermanentContractClassCache>,
+        DeployAccount,
+    ) {
+        let contract = ContractClass::from_path(path).unwrap();
+        let class_hash = felt_to_hash(&compute_deprecated_class_hash(&contract).unwrap());
+
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        state
+            .set_contract_class(&class_hash, &CompiledClass::Deprecated(Arc::new(contract)))
+            .unwrap();
+
+        let internal_deploy = DeployAccount::new(
+            class_hash,
+            Default::default(),
+            version,
+            0.into(),
+            vec![],
+            Vec::new(),
+            0.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+
+        (state, internal_deploy)
+    }
+
+    /// Executes `internal_deploy` on a transactional copy of `state`, so that several scenarios
+    /// can run against the same fixture.
+    fn execute_on_copy(
+        state: &CachedState<InMemoryStateReader, PermanentContractClassCache>,
+        internal_deploy: &DeployAccount,
+        block_context: &BlockContext,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        internal_deploy.execute(
+            &mut state.create_transactional().unwrap(),
+            block_context,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+    }
+
+    /// Executes `internal_deploy` like [execute_on_copy] and returns the error it fails with.
+    fn execute_expecting_error(
+        state: &CachedState<InMemoryStateReader, PermanentContractClassCache>,
+        internal_deploy: &DeployAccount,
+        block_context: &BlockContext,
+    ) -> TransactionError {
+        match execute_on_copy(state, internal_deploy, block_context) {
+            Ok(_) => panic!("expected the deploy account to fail"),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn get_state_selector() {
+        let path = PathBuf::from("starknet_programs/constructor.json");
+        let contract = ContractClass::from_path(path).unwrap();
+
+        let hash = compute_deprecated_class_hash(&contract).unwrap();
+        let class_hash = felt_to_hash(&hash);
+
+        let block_context = BlockContext::default();
+        let mut _state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+
+        let internal_deploy = DeployAccount::new(
+            class_hash,
+            Default::default(),
+            0.into(),
+            0.into(),
+            vec![10.into()],
+            Vec::new(),
+            0.into(),
+            StarknetChainId::TestNet2.to_felt(),
+        )
+        .unwrap();
+
+        let state_selector = internal_deploy.get_state_selector(block_context);
+
+        assert_eq!(
+            state_selector.contract_addresses,
+            vec![internal_deploy.contract_address]
+        );
+        assert_eq!(state_selector.class_hashes, vec![class_hash]);
+    }
+
+    #[test]
+    fn deploy_account_twice_should_fail() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let block_context = BlockContext::default();
+
+        let internal_deploy_error = DeployAccount::new(
+            *internal_deploy.class_hash(),
+            Default::default(),
+            1.into(),
+            1.into(),
+            vec![],
+            Vec::new(),
+            0.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+
+        internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_matches!(
+            internal_deploy_error
+                .execute(
+                    &mut state,
+                    &block_context,
+                    #[cfg(feature = "cairo-native")]
+                    None,
+                )
+                .unwrap_err(),
+            TransactionError::State(StateError::ContractAddressUnavailable(..))
+        )
+    }
+
+    #[test]
+    #[should_panic]
+    // Should panic at no calldata for constructor. Error managment not implemented yet.
+    fn deploy_account_constructor_should_fail() {
+        let (mut state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/constructor.json", 0.into());
+
+        internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn deploy_account_wrong_version() {
+        let chain_id = StarknetChainId::TestNet.to_felt();
+
+        // declare tx
+        let internal_declare = DeployAccount::new(
+            ClassHash([2; 32]),
+            VersionSpecificAccountTxFields::new_deprecated(9000),
+            2.into(),
+            Felt252::ZERO,
+            vec![],
+            vec![],
+            Felt252::ONE,
+            chain_id,
+        )
+        .unwrap();
+        let result = internal_declare.execute(
+            &mut CachedState::<InMemoryStateReader, PermanentContractClassCache>::default(),
+            &BlockContext::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        );
+
+        assert_matches!(
+        result,
+        Err(TransactionError::UnsupportedTxVersion(tx, ver, supp))
+        if tx == "DeployAccount" && ver == 2.into() && supp == vec![1]);
+    }
+
+    #[test]
+    fn execute_with_prefetched_nonce_does_not_read_nonce() {
+        let path = PathBuf::from("starknet_programs/account_without_validation.json");
+        let contract = ContractClass::from_path(path).unwrap();
+
+        let hash = compute_deprecated_class_hash(&contract).unwrap();
+        let class_hash = felt_to_hash(&hash);
+
+        let block_context = BlockContext::default();
+        let state_reader = Arc::new(CountingStateReader::default());
+        let mut state = CachedState::new(
+            state_reader.clone(),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+
+        let internal_deploy = DeployAccount::new(
+            class_hash,
+            Default::default(),
+            1.into(),
+            0.into(),
+            vec![],
+            Vec::new(),
+            0.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+
+        state
+            .set_contract_class(&class_hash, &CompiledClass::Deprecated(Arc::new(contract)))
+            .unwrap();
+        internal_deploy
+            .execute_with_prefetched_nonce(
+                &mut state,
+                &block_context,
+                Felt252::ZERO,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(state_reader.nonce_reads.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            state
+                .cache()
+                .nonce_writes
+                .get(internal_deploy.contract_address()),
+            Some(&Felt252::ONE)
+        );
+    }
+
+    #[test]
+    fn from_broadcasted_json_v1() {
+        let payload = serde_json::json!({
+            "type": "DEPLOY_ACCOUNT",
+            "version": "0x1",
+            "max_fee": "0x2386f26fc10000",
+            "nonce": "0x0",
+            "signature": [
+                "0x5a0d1e1cd5b5c68b4a1e0a4d3f0a2bfa1b8e0f6f0a1a3c6f1b5b0bb1c6d6a58",
+                "0x2d6c9e0b6e3a7f3a3e1a7c5f2d4e0b1c2a3f4e5d6c7b8a9f0e1d2c3b4a59687"
+            ],
+            "contract_address_salt": "0x4e2a7c51c5f7b1d6e",
+            "constructor_calldata": ["0x1", "0x2a"],
+            "class_hash": "0x25ec026985a3bf9d0cc1fe17326b245dfdc3ff89b8fde106542a3ea56c5a918"
+        });
+
+        let tx = DeployAccount::from_broadcasted_json(
+            payload.clone(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+
+        let class_hash =
+            Felt252::from_hex("0x25ec026985a3bf9d0cc1fe17326b245dfdc3ff89b8fde106542a3ea56c5a918")
+                .unwrap();
+        let salt = Felt252::from_hex("0x4e2a7c51c5f7b1d6e").unwrap();
+        let constructor_calldata = [Felt252::ONE, 42.into()];
+        assert_eq!(*tx.class_hash(), ClassHash::from(class_hash));
+        assert_eq!(*tx.contract_address_salt(), salt);
+        assert_eq!(tx.constructor_calldata(), &constructor_calldata);
+        assert_eq!(tx.version, Felt252::ONE);
+        assert_eq!(tx.nonce, Felt252::ZERO);
+        assert_eq!(
+            tx.signature(),
+            &[
+                Felt252::from_hex(
+                    "0x5a0d1e1cd5b5c68b4a1e0a4d3f0a2bfa1b8e0f6f0a1a3c6f1b5b0bb1c6d6a58",
+                )
+                .unwrap(),
+                Felt252::from_hex(
+                    "0x2d6c9e0b6e3a7f3a3e1a7c5f2d4e0b1c2a3f4e5d6c7b8a9f0e1d2c3b4a59687",
+                )
+                .unwrap(),
+            ]
+        );
+        assert_eq!(tx.account_tx_fields.max_fee(), 0x2386f26fc10000);
+
+        // The address and hash are derived from the payload's raw fields rather than through
+        // `DeployAccount::new`, so a field parsed into the wrong slot can't cancel itself out.
+        let contract_address = Address(
+            calculate_contract_address(
+                &salt,
+                &class_hash,
+                &constructor_calldata,
+                Address(Felt252::ZERO),
+            )
+            .unwrap(),
+        );
+        assert_eq!(*tx.contract_address(), contract_address);
+        assert_eq!(
+            *tx.hash_value(),
+            calculate_deploy_account_transaction_hash(
+                Felt252::ONE,
+                &contract_address,
+                class_hash,
+                &constructor_calldata,
+                0x2386f26fc10000,
+                Felt252::ZERO,
+                salt,
+                StarknetChainId::TestNet.to_felt(),
+            )
+            .unwrap()
+        );
+
+        let mut missing_max_fee = payload;
+        missing_max_fee.as_object_mut().unwrap().remove("max_fee");
+        assert_matches!(
+            DeployAccount::from_broadcasted_json(missing_max_fee, StarknetChainId::TestNet.to_felt()),
+            Err(TransactionError::InvalidBroadcastedTxField(field)) if field == "max_fee"
+        );
+    }
+
+    #[test]
+    fn from_broadcasted_json_rejects_v3() {
+        let payload = serde_json::json!({
+            "type": "DEPLOY_ACCOUNT",
+            "version": "0x3",
+            "signature": ["0x1", "0x2"],
+            "nonce": "0x0",
+            "contract_address_salt": "0x7",
+            "constructor_calldata": ["0x3"],
+            "class_hash": "0x2",
+            "resource_bounds": {
+                "l1_gas": { "max_amount": "0x186a0", "max_price_per_unit": "0x5af3107a4000" },
+                "l2_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" }
+            },
+            "tip": "0xa",
+            "paymaster_data": [],
+            "nonce_data_availability_mode": "L1",
+            "fee_data_availability_mode": "L2"
+        });
+
+        assert_matches!(
+            DeployAccount::from_broadcasted_json(payload, StarknetChainId::TestNet.to_felt()),
+            Err(TransactionError::UnsupportedTxVersion(tx, version, supported))
+            if tx == "DeployAccount" && version == Felt252::THREE && supported == vec![1]
+        );
+    }
+
+    #[test]
+    fn from_sn_api_transaction_strict_rejects_out_of_range_salt() {
+        use starknet_api::{
+            core::{ClassHash as SNClassHash, ContractAddressSalt, Nonce},
+            hash::StarkFelt,
+            transaction::{
+                Calldata, DeployAccountTransaction, DeployAccountTransactionV1, Fee,
+                TransactionSignature,
+            },
+        };
+
+        // Field prime + 1, reduced to 1 when converted to a Felt252
+        let salt = StarkFelt::try_from(
+            "0x0800000000000011000000000000000000000000000000000000000000000002",
+        )
+        .unwrap();
+        let tx = DeployAccountTransaction::V1(DeployAccountTransactionV1 {
+            max_fee: Fee(0),
+            signature: TransactionSignature(vec![]),
+            nonce: Nonce(StarkFelt::from(0u32)),
+            class_hash: SNClassHash(StarkFelt::from(1u32)),
+            contract_address_salt: ContractAddressSalt(salt),
+            constructor_calldata: Calldata(Arc::new(vec![])),
+        });
+
+        assert_matches!(
+            DeployAccount::from_sn_api_transaction(tx.clone(), Felt252::ONE, true),
+            Err(TransactionError::SaltOutOfRange(_))
+        );
+        let deploy = DeployAccount::from_sn_api_transaction(tx, Felt252::ONE, false).unwrap();
+        assert_eq!(deploy.contract_address_salt(), &Felt252::ONE);
+    }
+
+    #[test]
+    fn apply_resolves_class_at_replay_block_number() {
+        let account_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/account_without_validation.json").unwrap(),
+        ));
+        let CompiledClass::Deprecated(deprecated_account_class) = &account_class else {
+            unreachable!()
+        };
+        let class_hash =
+            felt_to_hash(&compute_deprecated_class_hash(deprecated_account_class).unwrap());
+        // A later version of the class, which isn't an account class.
+        let newer_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/constructor.json").unwrap(),
+        ));
+
+        let contract_class_cache = Arc::new(VersionedContractClassCache::default());
+        contract_class_cache.set_contract_class_at(class_hash, 0, account_class.clone());
+        contract_class_cache.set_contract_class_at(class_hash, 10, newer_class.clone());
+
+        let mut internal_deploy = DeployAccount::new(
+            class_hash,
+            Default::default(),
+            1.into(),
+            0.into(),
+            vec![],
+            Vec::new(),
+            0.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            contract_class_cache.clone(),
+        );
+        let assert_cache_unchanged = |state: &CachedState<_, _>| {
+            assert!(!state
+                .contract_class_cache_private
+                .read()
+                .unwrap()
+                .contains_key(&class_hash));
+            assert_eq!(
+                contract_class_cache.get_contract_class_at(class_hash, 0),
+                Some(account_class.clone())
+            );
+            assert_eq!(
+                contract_class_cache.get_contract_class(class_hash),
+                Some(newer_class.clone())
+            );
+        };
+
+        // At block 10 the class isn't an account class anymore.
+        internal_deploy
+            .options_mut()
+            .set_replay_block_number(Some(10));
+        assert_matches!(
+            internal_deploy.execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            ),
+            Err(TransactionError::NotAnAccountClass(_))
+        );
+        assert_cache_unchanged(&state);
+
+        internal_deploy
+            .options_mut()
+            .set_replay_block_number(Some(5));
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        // Only the block 0 version of the class has a `__validate_deploy__` to run.
+        assert_eq!(
+            tx_info.validate_info.unwrap().entry_point_selector,
+            Some(*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR)
+        );
+        assert_cache_unchanged(&state);
+    }
+
+    #[test]
+    fn report_fee_overage_without_revert() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        let max_fee = 1;
+        let mut internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(max_fee),
+            skip_fee_transfer: true,
+            ..internal_deploy
+        };
+        internal_deploy
+            .options_mut()
+            .set_report_fee_overage_without_revert(true);
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let calculated_fee =
+            calculate_tx_fee(&tx_info.actual_resources, &block_context, &FeeType::Eth).unwrap();
+        assert!(calculated_fee > max_fee);
+        assert_eq!(tx_info.revert_error, None);
+        assert!(tx_info.call_info.is_some());
+        assert_eq!(tx_info.fee_overage, Some(calculated_fee - max_fee));
+    }
+
+    /// Charges twice the fee of the default strategy.
+    struct DoubleFee;
+
+    impl FeeStrategy for DoubleFee {
+        fn calculate_tx_fee(
+            &self,
+            resources: &HashMap<String, usize>,
+            block_context: &BlockContext,
+            fee_type: &FeeType,
+        ) -> Result<u128, TransactionError> {
+            Ok(FeeRounding::default().calculate_tx_fee(resources, block_context, fee_type)? * 2)
+        }
+    }
+
+    #[test]
+    fn execute_with_custom_fee_strategy() {
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+        let execute = |fee_strategy: Option<&dyn FeeStrategy>| {
+            let (mut state, internal_deploy) = deploy_account_fixture(
+                "starknet_programs/account_without_validation.json",
+                1.into(),
+            );
+            let internal_deploy = DeployAccount {
+                account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(1_000_000),
+                skip_fee_transfer: true,
+                ..internal_deploy
+            };
+            internal_deploy
+                .execute_with_fee_strategy(
+                    &mut state,
+                    &block_context,
+                    fee_strategy,
+                    #[cfg(feature = "cairo-native")]
+                    None,
+                )
+                .unwrap()
+        };
+
+        let default_tx_info = execute(None);
+        let doubled_tx_info = execute(Some(&DoubleFee));
+
+        assert!(default_tx_info.actual_fee > 0);
+        assert_eq!(
+            default_tx_info.actual_fee,
+            calculate_tx_fee(
+                &default_tx_info.actual_resources,
+                &block_context,
+                &FeeType::Eth
+            )
+            .unwrap()
+        );
+        assert_eq!(doubled_tx_info.actual_fee, default_tx_info.actual_fee * 2);
+        assert_eq!(doubled_tx_info.revert_error, None);
+    }
+
+    #[test]
+    fn deploy_account_into_transaction_executes_identically() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut tx_state = state.clone_for_testing();
+        let block_context = BlockContext::default();
+
+        let direct_result = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        let tx_result = Transaction::from(internal_deploy)
+            .execute(
+                &mut tx_state,
+                &block_context,
+                INITIAL_GAS_COST,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(direct_result, tx_result);
+    }
+
+    #[test]
+    fn deploy_account_fixture_deploys_account() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+
+        internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(
+            State::get_class_hash_at(&mut state, internal_deploy.contract_address()).unwrap(),
+            *internal_deploy.class_hash()
+        );
+        assert_eq!(
+            State::get_nonce_at(&mut state, internal_deploy.contract_address()).unwrap(),
+            Felt252::ONE
+        );
+    }
+
+    fn deploy_account_with_fields(
+        account_tx_fields: VersionSpecificAccountTxFields,
+    ) -> DeployAccount {
+        let version = match account_tx_fields {
+            VersionSpecificAccountTxFields::Deprecated(_) => Felt252::ONE,
+            VersionSpecificAccountTxFields::Current(_) => Felt252::THREE,
+        };
+        DeployAccount::new(
+            ClassHash([2; 32]),
+            account_tx_fields,
+            version,
+            Felt252::ZERO,
+            vec![],
+            vec![],
+            Felt252::ONE,
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap()
+    }
+
+    fn current_account_tx_fields(
+        l1_resource_bounds: Option<ResourceBounds>,
+        l2_resource_bounds: Option<ResourceBounds>,
+        tip: u64,
+    ) -> VersionSpecificAccountTxFields {
+        VersionSpecificAccountTxFields::Current(CurrentAccountTxFields {
+            resource_bounds: ResourceBoundsMapping {
+                l1_gas: l1_resource_bounds,
+                l2_gas: l2_resource_bounds,
+            },
+            tip,
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+        })
+    }
+
+    #[test]
+    fn max_possible_fee_deprecated() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(1234));
+        assert_eq!(internal_deploy.max_possible_fee(), Some(1234));
+    }
+
+    #[test]
+    fn max_possible_fee_current() {
+        let internal_deploy = deploy_account_with_fields(current_account_tx_fields(
+            Some(ResourceBounds {
+                max_amount: 10,
+                max_price_per_unit: 3,
+            }),
+            Some(ResourceBounds {
+                max_amount: 2,
+                max_price_per_unit: 5,
+            }),
+            7,
+        ));
+        assert_eq!(internal_deploy.max_possible_fee(), Some(30 + 10 + 7));
+    }
+
+    #[test]
+    fn max_possible_fee_current_overflow() {
+        let internal_deploy = deploy_account_with_fields(current_account_tx_fields(
+            Some(ResourceBounds {
+                max_amount: u64::MAX,
+                max_price_per_unit: u128::MAX,
+            }),
+            None,
+            0,
+        ));
+        assert_eq!(internal_deploy.max_possible_fee(), None);
+
+        let internal_deploy = deploy_account_with_fields(current_account_tx_fields(
+            Some(ResourceBounds {
+                max_amount: 1,
+                max_price_per_unit: u128::MAX,
+            }),
+            None,
+            1,
+        ));
+        assert_eq!(internal_deploy.max_possible_fee(), None);
+    }
+
+    #[test]
+    fn new_with_address_validation_rejects_mismatched_salt() {
+        // Policy: the salt must be the class hash itself.
+        let salt_commits_to_class_hash = |address: &Address,
+                                          class_hash: &ClassHash|
+         -> Result<(), TransactionError> {
+            let class_hash = Felt252::from_bytes_be(&class_hash.0);
+            let expected_address =
+                calculate_contract_address(&class_hash, &class_hash, &[], Address(Felt252::ZERO))?;
+            if address.0 != expected_address {
+                return Err(TransactionError::CustomError(
+                    "salt doesn't commit to the class hash".to_string(),
+                ));
+            }
+            Ok(())
+        };
+        let class_hash = ClassHash([2; 32]);
+        let new_deploy = |salt: Felt252| {
+            DeployAccount::new_with_address_validation(
+                class_hash,
+                Default::default(),
+                1.into(),
+                Felt252::ZERO,
+                vec![],
+                vec![],
+                salt,
+                StarknetChainId::TestNet.to_felt(),
+                salt_commits_to_class_hash,
+            )
+        };
+
+        assert!(new_deploy(Felt252::from_bytes_be(&class_hash.0)).is_ok());
+        assert_matches!(
+            new_deploy(Felt252::ONE),
+            Err(TransactionError::CustomError(_))
+        );
+    }
+
+    #[test]
+    fn new_with_counterfactual_address_test() {
+        let chain_id = StarknetChainId::TestNet.to_felt();
+        let new_deploy = |counterfactual_address: Address| {
+            DeployAccount::new_with_counterfactual_address(
+                ClassHash([2; 32]),
+                VersionSpecificAccountTxFields::new_deprecated(10),
+                1.into(),
+                Felt252::ZERO,
+                vec![3.into()],
+                vec![],
+                Felt252::ONE,
+                chain_id,
+                counterfactual_address,
+            )
+        };
+        let expected_deploy = DeployAccount::new(
+            ClassHash([2; 32]),
+            VersionSpecificAccountTxFields::new_deprecated(10),
+            1.into(),
+            Felt252::ZERO,
+            vec![3.into()],
+            vec![],
+            Felt252::ONE,
+            chain_id,
+        )
+        .unwrap();
+        let derived_address = expected_deploy.contract_address().clone();
+
+        let deploy = new_deploy(derived_address.clone()).unwrap();
+        assert_eq!(deploy.contract_address(), &derived_address);
+        assert_eq!(deploy.hash_value(), expected_deploy.hash_value());
+
+        assert_matches!(
+            new_deploy(Address(1234.into())),
+            Err(TransactionError::CounterfactualAddressMismatch(counterfactual, derived))
+            if counterfactual == Address(1234.into()) && derived == derived_address
+        );
+    }
+
+    #[test]
+    fn fee_token_address_by_fee_type() {
+        let eth_fee_token_address = Address(1.into());
+        let strk_fee_token_address = Address(2.into());
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address = FeeTokenAddresses::new(
+            eth_fee_token_address.clone(),
+            strk_fee_token_address.clone(),
+        );
+
+        let v1_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        assert_eq!(
+            v1_deploy.fee_token_address(&block_context),
+            eth_fee_token_address
+        );
+
+        let v3_deploy = deploy_account_with_fields(current_account_tx_fields(None, None, 0));
+        assert_eq!(
+            v3_deploy.fee_token_address(&block_context),
+            strk_fee_token_address
+        );
+    }
+
+    #[test]
+    fn check_fee_balance_with_prefetched_balance_skips_balance_read() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        let block_context = BlockContext::default();
+        let state_reader = Arc::new(CountingStateReader::default());
+        let mut state = CachedState::new(
+            state_reader.clone(),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+
+        internal_deploy
+            .check_fee_balance(
+                &mut state,
+                &block_context,
+                &FeeType::Eth,
+                Some((10.into(), Felt252::ZERO)),
+            )
+            .unwrap();
+        assert_eq!(state_reader.storage_reads.load(Ordering::SeqCst), 0);
+
+        // Without a prefetched balance, the (zero) balance is read from the fee token storage.
+        assert_matches!(
+            internal_deploy.check_fee_balance(&mut state, &block_context, &FeeType::Eth, None),
+            Err(TransactionError::MaxFeeExceedsBalance(..))
+        );
+        assert_eq!(state_reader.storage_reads.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn check_fee_balance_with_zero_resource_bounds_skips_balance_read() {
+        let zero_bounds = || {
+            Some(ResourceBounds {
+                max_amount: 0,
+                max_price_per_unit: 0,
+            })
+        };
+        let internal_deploy =
+            deploy_account_with_fields(current_account_tx_fields(zero_bounds(), zero_bounds(), 0));
+        let block_context = BlockContext::default();
+        let state_reader = Arc::new(CountingStateReader::default());
+        let mut state = CachedState::new(
+            state_reader.clone(),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+
+        internal_deploy
+            .check_fee_balance(&mut state, &block_context, &FeeType::Strk, None)
+            .unwrap();
+        assert_eq!(state_reader.storage_reads.load(Ordering::SeqCst), 0);
+
+        // A nonzero L2 gas bound is enough for the balance to be read.
+        let internal_deploy = deploy_account_with_fields(current_account_tx_fields(
+            zero_bounds(),
+            Some(ResourceBounds {
+                max_amount: 1,
+                max_price_per_unit: 1,
+            }),
+            0,
+        ));
+        internal_deploy
+            .check_fee_balance(&mut state, &block_context, &FeeType::Strk, None)
+            .unwrap();
+        assert_eq!(state_reader.storage_reads.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn data_gas_and_compute_steps_make_up_minimal_fee() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(7, 0);
+
+        let data_gas = internal_deploy.estimate_data_gas();
+        let compute_steps = internal_deploy.estimate_compute_steps(&block_context);
+        assert!(data_gas > 0);
+        assert!(compute_steps > 0);
+
+        let compute_gas =
+            compute_steps as f64 * block_context.cairo_resource_fee_weights["n_steps"];
+        let expected_fee = (data_gas as f64 + compute_gas).ceil() as u128 * 7;
+        assert_eq!(
+            internal_deploy
+                .estimate_minimal_fee(&block_context)
+                .unwrap(),
+            expected_fee
+        );
+    }
+
+    #[test]
+    fn os_resources_override_changes_minimal_fee() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+        let default_minimal_fee = internal_deploy
+            .estimate_minimal_fee(&block_context)
+            .unwrap();
+
+        let mut os_resources = block_context.os_resources().clone();
+        os_resources.set_tx_resources(
+            TransactionType::DeployAccount,
+            ExecutionResources {
+                n_steps: 10_000,
+                n_memory_holes: 0,
+                builtin_instance_counter: HashMap::new(),
+            },
+        );
+        block_context.set_os_resources(os_resources);
+
+        assert_eq!(
+            internal_deploy.estimate_compute_steps(&block_context),
+            10_000
+        );
+        let minimal_fee = internal_deploy
+            .estimate_minimal_fee(&block_context)
+            .unwrap();
+        assert_eq!(
+            minimal_fee,
+            internal_deploy.estimate_data_gas()
+                + (10_000.0 * block_context.cairo_resource_fee_weights["n_steps"]) as u128
+        );
+        assert!(minimal_fee > default_minimal_fee);
+
+        // The overhead charged on execution comes from the same table.
+        let overhead_tx_info = internal_deploy
+            .overhead_execution_info(&block_context)
+            .unwrap();
+        assert_eq!(overhead_tx_info.actual_resources["n_steps"], 10_000);
+    }
+
+    #[test]
+    fn zero_gas_prices_yield_zero_minimal_fee() {
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(0, 0);
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+
+        let eth_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        let mut strk_deploy = eth_deploy.clone();
+        strk_deploy.options.fee_type_override = Some(FeeType::Strk);
+        for mut internal_deploy in [eth_deploy, strk_deploy] {
+            for fee_rounding in [FeeRounding::Floor, FeeRounding::Ceil, FeeRounding::Round] {
+                internal_deploy.options_mut().set_fee_rounding(fee_rounding);
+                assert_eq!(
+                    internal_deploy
+                        .estimate_minimal_fee(&block_context)
+                        .unwrap(),
+                    0
+                );
+                // Any max fee covers the minimal fee, so only the balance is checked.
+                assert!(internal_deploy
+                    .check_fee_balance(
+                        &mut state,
+                        &block_context,
+                        &internal_deploy.fee_type(),
+                        Some((10.into(), Felt252::ZERO)),
+                    )
+                    .is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn minimal_fee_accounts_for_constructor_storage() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+        let class = |path: &str| {
+            CompiledClass::Deprecated(Arc::new(ContractClass::from_path(path).unwrap()))
+        };
+        let minimal_fee = |contract_class: Option<&CompiledClass>| {
+            internal_deploy
+                .estimate_minimal_fee_for_class(&block_context, contract_class)
+                .unwrap()
+        };
+
+        let without_constructor = class("starknet_programs/account_without_validation.json");
+        let with_constructor = class("starknet_programs/Account.json");
+        assert_eq!(
+            DeployAccount::estimated_state_changes_for_class(&without_constructor),
+            DeployAccount::estimated_state_changes()
+        );
+        assert_eq!(
+            DeployAccount::estimated_state_changes_for_class(&with_constructor).n_storage_updates,
+            2
+        );
+        assert_eq!(
+            DeployAccount::estimated_state_changes_for_class(&with_constructor)
+                .n_modified_contracts,
+            1
+        );
+
+        assert_eq!(minimal_fee(Some(&without_constructor)), minimal_fee(None));
+        assert!(minimal_fee(Some(&with_constructor)) > minimal_fee(Some(&without_constructor)));
+
+        // A max fee covering only the constructor-less estimate is rejected for a class with a
+        // constructor once the estimate takes it into account.
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        state
+            .set_contract_class(internal_deploy.class_hash(), &with_constructor)
+            .unwrap();
+        let mut internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(minimal_fee(None)),
+            ..internal_deploy.clone()
+        };
+        let mut check_fee_balance = |internal_deploy: &DeployAccount| {
+            internal_deploy.check_fee_balance(
+                &mut state,
+                &block_context,
+                &FeeType::Eth,
+                Some((u64::MAX.into(), Felt252::ZERO)),
+            )
+        };
+        assert!(check_fee_balance(&internal_deploy).is_ok());
+        internal_deploy
+            .options_mut()
+            .set_constructor_aware_fee_estimate(true);
+        assert_matches!(
+            check_fee_balance(&internal_deploy),
+            Err(TransactionError::MaxFeeTooLow(..))
+        );
+    }
+
+    #[test]
+    fn max_fee_too_low_reports_estimated_minimal_fee() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(7, 0);
+        let minimal_fee = internal_deploy
+            .estimate_minimal_fee(&block_context)
+            .unwrap();
+        let internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(minimal_fee - 1),
+            ..internal_deploy
+        };
+
+        assert_matches!(
+            execute_expecting_error(&state, &internal_deploy, &block_context),
+            TransactionError::MaxFeeTooLow(max_fee, reported_minimal_fee)
+            if max_fee == minimal_fee - 1 && reported_minimal_fee == minimal_fee
+        );
+    }
+
+    #[test]
+    fn fee_rounding_changes_minimal_fee_at_fractional_gas() {
+        let mut internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(7, 0);
+
+        // The estimated compute steps are weighted into a fractional amount of L1 gas.
+        let gas_usage = internal_deploy.estimate_data_gas() as f64
+            + internal_deploy.estimate_compute_steps(&block_context) as f64
+                * block_context.cairo_resource_fee_weights["n_steps"];
+        assert_ne!(gas_usage.fract(), 0.0);
+
+        let mut minimal_fee = |fee_rounding| {
+            internal_deploy.options_mut().set_fee_rounding(fee_rounding);
+            internal_deploy
+                .estimate_minimal_fee(&block_context)
+                .unwrap()
+        };
+        let ceil = minimal_fee(FeeRounding::Ceil);
+        let floor = minimal_fee(FeeRounding::Floor);
+        let round = minimal_fee(FeeRounding::Round);
+
+        assert_eq!(ceil, gas_usage.ceil() as u128 * 7);
+        assert_eq!(floor, gas_usage.floor() as u128 * 7);
+        assert_eq!(round, gas_usage.round() as u128 * 7);
+        assert_eq!(ceil, floor + 7);
+        assert_eq!(
+            ceil,
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10))
+                .estimate_minimal_fee(&block_context)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn apply_with_both_skip_flags_touches_no_state() {
+        // The class isn't declared, so any attempt to load it would fail.
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let internal_deploy = DeployAccount {
+            skip_validate: true,
+            skip_execute: true,
+            ..deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10))
+        };
+
+        let tx_info = internal_deploy
+            .apply(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(state.cache(), &StateCache::default());
+        assert!(tx_info.validate_info.is_none());
+        assert!(tx_info.call_info.is_none());
+        assert_eq!(
+            tx_info.actual_resources,
+            calculate_tx_resources(
+                ExecutionResourcesManager::default(),
+                &[],
+                TransactionType::DeployAccount,
+                DeployAccount::estimated_state_changes(),
+                None,
+                0,
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn execute_into_shared_transactional_state() {
+        let (mut state, first_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let second_deploy = DeployAccount::new(
+            *first_deploy.class_hash(),
+            Default::default(),
+            1.into(),
+            0.into(),
+            vec![],
+            Vec::new(),
+            1.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+        let block_context = BlockContext::default();
+
+        let mut txn_state = state.create_transactional().unwrap();
+        for deploy in [&first_deploy, &second_deploy] {
+            deploy
+                .execute_into(
+                    &mut txn_state,
+                    &block_context,
+                    #[cfg(feature = "cairo-native")]
+                    None,
+                )
+                .unwrap();
+        }
+
+        // Nothing is written to the parent state until the shared layer is committed.
+        assert!(state.cache().class_hash_writes.is_empty());
+        state
+            .apply_state_update(&StateDiff::from_cached_state(txn_state.cache()).unwrap())
+            .unwrap();
+
+        for deploy in [&first_deploy, &second_deploy] {
+            assert_eq!(
+                State::get_class_hash_at(&mut state, deploy.contract_address()).unwrap(),
+                *deploy.class_hash()
+            );
+        }
+    }
+
+    #[test]
+    fn execute_into_leaves_shared_state_untouched_on_failure() {
+        let (state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_storage_branch.json",
+            1.into(),
+        );
+        // With storage key 1 set, the constructor writes storage key 2.
+        internal_deploy
+            .options_mut()
+            .set_storage_overlay(HashMap::from([(
+                (internal_deploy.contract_address().clone(), Felt252::ONE),
+                Felt252::ONE,
+            )]));
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        let mut txn_state = state.create_transactional().unwrap();
+        let cache_before = txn_state.cache().clone();
+        // The deploy goes through, but its fee exceeds its zero max fee.
+        assert_matches!(
+            internal_deploy.execute_into(
+                &mut txn_state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            ),
+            Err(TransactionError::ActualFeeExceedsMaxFee(..))
+        );
+        assert_eq!(txn_state.cache(), &cache_before);
+        assert!(internal_deploy.address_is_available(&txn_state).unwrap());
+    }
+
+    #[test]
+    fn execute_on_other_chain_should_fail() {
+        let contract =
+            ContractClass::from_path("starknet_programs/account_without_validation.json").unwrap();
+        let class_hash = felt_to_hash(&compute_deprecated_class_hash(&contract).unwrap());
+
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        state
+            .set_contract_class(&class_hash, &CompiledClass::Deprecated(Arc::new(contract)))
+            .unwrap();
+
+        let internal_deploy = DeployAccount::new(
+            class_hash,
+            Default::default(),
+            1.into(),
+            0.into(),
+            vec![],
+            Vec::new(),
+            0.into(),
+            StarknetChainId::MainNet.to_felt(),
+        )
+        .unwrap();
+        assert_eq!(
+            internal_deploy.chain_id(),
+            Some(StarknetChainId::MainNet.to_felt())
+        );
+
+        let result = internal_deploy.execute(
+            &mut state,
+            &BlockContext::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        );
+
+        assert_matches!(
+            result,
+            Err(TransactionError::ChainIdMismatch(tx_chain_id, block_chain_id))
+            if tx_chain_id == StarknetChainId::MainNet.to_felt()
+                && block_chain_id == StarknetChainId::TestNet.to_felt()
+        );
+        assert_eq!(
+            State::get_nonce_at(&mut state, internal_deploy.contract_address()).unwrap(),
+            Felt252::ZERO
+        );
+    }
+
+    #[test]
+    fn constructor_caller_override_is_observed_by_constructor() {
+        let (mut state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_caller.json",
+            1.into(),
+        );
+        let caller_address = Address(42.into());
+        internal_deploy
+            .options_mut()
+            .set_constructor_caller_override(Some(caller_address.clone()));
+
+        internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let storage_key = get_storage_var_address("constructor_caller", &[]).unwrap();
+        assert_eq!(
+            State::get_storage_at(
+                &mut state,
+                &(
+                    internal_deploy.contract_address().clone(),
+                    storage_key.to_bytes_be()
+                )
+            )
+            .unwrap(),
+            caller_address.0
+        );
+    }
+
+    #[cfg(feature = "replay_benchmark")]
+    #[test]
+    fn replay_benchmark_captures_initial_values() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_caller.json",
+            1.into(),
+        );
+
+        internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let storage_key = get_storage_var_address("constructor_caller", &[]).unwrap();
+        assert_eq!(
+            state.captured_storage_initial_values().get(&(
+                internal_deploy.contract_address().clone(),
+                storage_key.to_bytes_be()
+            )),
+            Some(&Felt252::ZERO)
+        );
+        assert_eq!(
+            state
+                .captured_class_hash_initial_values()
+                .get(internal_deploy.contract_address()),
+            Some(UNINITIALIZED_CLASS_HASH)
+        );
+    }
+
+    #[test]
+    fn address_is_not_available_after_deploy() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        assert!(internal_deploy.address_is_available(&state).unwrap());
+
+        internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert!(!internal_deploy.address_is_available(&state).unwrap());
+    }
+
+    #[test]
+    fn fee_balance_storage_key_matches_slots_read_by_check_fee_balance() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(10),
+            ..internal_deploy
+        };
+        let block_context = BlockContext::default();
+
+        // The account has no balance; the slots are read nonetheless.
+        assert_matches!(
+            internal_deploy.check_fee_balance(&mut state, &block_context, &FeeType::Eth, None),
+            Err(TransactionError::MaxFeeExceedsBalance(..))
+        );
+
+        let fee_token_address = internal_deploy.fee_token_address(&block_context);
+        let (low_key, high_key) = internal_deploy.fee_balance_storage_key();
+        let read_slots: HashSet<StorageEntry> = state
+            .cache()
+            .storage_initial_values()
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(
+            read_slots,
+            HashSet::from([
+                (fee_token_address.clone(), low_key.to_bytes_be()),
+                (fee_token_address, high_key.to_bytes_be()),
+            ])
+        );
+    }
+
+    #[test]
+    fn v3_deploy_account_without_l1_bounds_should_fail() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let l1_bounds = [
+            None,
+            Some(ResourceBounds {
+                max_amount: 0,
+                max_price_per_unit: 12,
+            }),
+        ];
+
+        for l1_resource_bounds in l1_bounds {
+            let deploy = DeployAccount {
+                version: 3.into(),
+                account_tx_fields: current_account_tx_fields(l1_resource_bounds, None, 0),
+                ..internal_deploy.clone()
+            };
+            assert_matches!(
+                deploy.execute(
+                    &mut state,
+                    &BlockContext::default(),
+                    #[cfg(feature = "cairo-native")]
+                    None,
+                ),
+                Err(TransactionError::MissingResourceBounds)
+            );
+        }
+    }
+
+    #[test]
+    fn build_validate_entry_point_test() {
+        let internal_deploy = DeployAccount::new(
+            ClassHash([2; 32]),
+            VersionSpecificAccountTxFields::new_deprecated(0),
+            1.into(),
+            Felt252::ZERO,
+            vec![7.into(), 8.into()],
+            vec![],
+            5.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+
+        let call = internal_deploy.build_validate_entry_point();
+        assert_eq!(call.call_type, CallType::Call);
+        assert_eq!(&call.contract_address, internal_deploy.contract_address());
+        assert_eq!(call.code_address, None);
+        assert_eq!(call.class_hash, None);
+        assert_eq!(
+            call.calldata,
+            vec![
+                Felt252::from_bytes_be(&[2; 32]),
+                5.into(),
+                7.into(),
+                8.into()
+            ]
+        );
+        assert_eq!(call.caller_address, Address(Felt252::ZERO));
+        assert_eq!(
+            call.entry_point_selector,
+            *VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR
+        );
+        assert_eq!(call.entry_point_type, EntryPointType::External);
+        assert_eq!(call.initial_gas, INITIAL_GAS_COST);
+    }
+
+    #[test]
+    fn handle_nonce_with_max_nonce_gap() {
+        let (_, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let handle_nonce = |nonce: u64, max_nonce_gap: u64| {
+            let mut state = CachedState::new(
+                Arc::new(InMemoryStateReader::default()),
+                Arc::new(PermanentContractClassCache::default()),
+            );
+            state
+                .cache_mut()
+                .nonce_initial_values_mut()
+                .insert(internal_deploy.contract_address().clone(), 5.into());
+            let mut deploy = DeployAccount {
+                nonce: nonce.into(),
+                ..internal_deploy.clone()
+            };
+            deploy.options_mut().set_max_nonce_gap(max_nonce_gap);
+            deploy.handle_nonce(&mut state, None)
+        };
+
+        assert!(handle_nonce(5, 2).is_ok());
+        assert!(handle_nonce(6, 2).is_ok());
+        assert!(handle_nonce(7, 2).is_ok());
+        assert_matches!(
+            handle_nonce(8, 2),
+            Err(TransactionError::InvalidTransactionNonce(..))
+        );
+        assert_matches!(
+            handle_nonce(4, 2),
+            Err(TransactionError::InvalidTransactionNonce(..))
+        );
+        // Without a gap the nonce must match exactly.
+        assert_matches!(
+            handle_nonce(6, 0),
+            Err(TransactionError::InvalidTransactionNonce(..))
+        );
+    }
+
+    #[test]
+    fn execute_with_nonce_gap_moves_nonce_past_transaction_nonce() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut internal_deploy = DeployAccount {
+            nonce: 2.into(),
+            ..internal_deploy
+        };
+        internal_deploy.options_mut().set_max_nonce_gap(2);
+
+        let mut block_context = BlockContext::default();
+        for increment_nonce_after_execution in [false, true] {
+            block_context.set_increment_nonce_after_execution(increment_nonce_after_execution);
+            let mut state = state.create_transactional().unwrap();
+            let tx_info = internal_deploy
+                .execute(
+                    &mut state,
+                    &block_context,
+                    #[cfg(feature = "cairo-native")]
+                    None,
+                )
+                .unwrap();
+
+            assert_eq!(tx_info.revert_error, None);
+            assert_eq!(
+                state
+                    .get_nonce_at(internal_deploy.contract_address())
+                    .unwrap(),
+                Felt252::THREE
+            );
+        }
+        assert_eq!(internal_deploy.nonce_after(), Felt252::THREE);
+    }
+
+    #[test]
+    fn summary_of_successful_deploy() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let n_steps = tx_info.actual_resources["n_steps"];
+        assert!(n_steps > 0);
+        assert_eq!(
+            tx_info.summary(),
+            ExecSummary {
+                actual_fee: 0,
+                reverted: false,
+                n_steps,
+                revert_reason: None,
+            }
+        );
+        assert_eq!(
+            tx_info.summary().to_string(),
+            format!("fee: 0, reverted: false, n_steps: {n_steps}")
+        );
+    }
+
+    #[test]
+    fn constructor_self_call_should_fail() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_self_call.json",
+            1.into(),
+        );
+
+        assert_matches!(
+            execute_expecting_error(&state, &internal_deploy, &BlockContext::default()),
+            TransactionError::ConstructorFailed(error)
+            if matches!(
+                *error,
+                TransactionError::ConstructorSelfCall(ref address)
+                if address == internal_deploy.contract_address()
+            )
+        );
+    }
+
+    #[test]
+    fn constructor_failure_is_tagged_with_its_phase() {
+        // The class has no constructor, so constructor calldata can't be consumed.
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let internal_deploy = DeployAccount {
+            constructor_calldata: vec![1.into()],
+            ..internal_deploy
+        };
+
+        assert_matches!(
+            execute_expecting_error(&state, &internal_deploy, &BlockContext::default()),
+            TransactionError::ConstructorFailed(error)
+            if matches!(*error, TransactionError::EmptyConstructorCalldata)
+        );
+    }
+
+    #[test]
+    fn validation_failure_is_tagged_with_its_phase() {
+        // The account checks the transaction signature on `__validate_deploy__`, but none is
+        // provided.
+        let (state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/Account.json", 1.into());
+        let internal_deploy = DeployAccount {
+            constructor_calldata: vec![1.into()],
+            ..internal_deploy
+        };
+
+        assert_matches!(
+            execute_expecting_error(&state, &internal_deploy, &BlockContext::default()),
+            TransactionError::ValidationFailed(_)
+        );
+    }
+
+    #[test]
+    fn verify_stark_signature_with_known_key() {
+        let private_key = FieldElement::from(0x1234_u64);
+        let public_key = Felt252::from_bytes_be(&get_public_key(&private_key).to_bytes_be());
+
+        let unsigned_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        assert_eq!(
+            unsigned_deploy.message_hash(),
+            *unsigned_deploy.hash_value()
+        );
+        assert!(!unsigned_deploy.verify_stark_signature(public_key));
+
+        let message_hash =
+            FieldElement::from_bytes_be(&unsigned_deploy.message_hash().to_bytes_be()).unwrap();
+        let k = rfc6979_generate_k(&message_hash, &private_key, None);
+        let signature = sign(&private_key, &message_hash, &k).unwrap();
+        let signed_deploy = DeployAccount {
+            signature: vec![
+                Felt252::from_bytes_be(&signature.r.to_bytes_be()),
+                Felt252::from_bytes_be(&signature.s.to_bytes_be()),
+            ],
+            ..unsigned_deploy
+        };
+
+        assert!(signed_deploy.verify_stark_signature(public_key));
+        assert!(!signed_deploy.verify_stark_signature(public_key + Felt252::ONE));
+    }
+
+    #[test]
+    fn with_bounds_from_estimate_applies_margin() {
+        let l2_resource_bounds = Some(ResourceBounds {
+            max_amount: 5,
+            max_price_per_unit: 6,
+        });
+        let internal_deploy = deploy_account_with_fields(current_account_tx_fields(
+            None,
+            l2_resource_bounds.clone(),
+            3,
+        ));
+        let estimate = FeeEstimate {
+            gas: 1000,
+            gas_price: 10,
+        };
+
+        let bounded_deploy = internal_deploy
+            .with_bounds_from_estimate(&estimate, 20)
+            .unwrap();
+
+        let expected_deploy = deploy_account_with_fields(current_account_tx_fields(
+            Some(ResourceBounds {
+                max_amount: 1200,
+                max_price_per_unit: 12,
+            }),
+            l2_resource_bounds,
+            3,
+        ));
+        assert_eq!(
+            bounded_deploy.max_possible_fee(),
+            Some(1200 * 12 + 5 * 6 + 3)
+        );
+        assert_eq!(bounded_deploy.hash_value(), expected_deploy.hash_value());
+        assert_ne!(bounded_deploy.hash_value(), internal_deploy.hash_value());
+
+        let v1_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        assert_matches!(
+            v1_deploy.with_bounds_from_estimate(&estimate, 20),
+            Err(TransactionError::UnsupportedTxVersion(..))
+        );
+    }
+
+    #[test]
+    fn new_with_address_derivation_uses_custom_scheme() {
+        let new_deploy = |derive_address: fn(
+            &Felt252,
+            &Felt252,
+            &[Felt252],
+            &Address,
+        ) -> Result<Felt252, TransactionError>| {
+            DeployAccount::new_with_address_derivation(
+                ClassHash([2; 32]),
+                VersionSpecificAccountTxFields::new_deprecated(10),
+                Felt252::ONE,
+                Felt252::ZERO,
+                vec![],
+                vec![],
+                Felt252::ONE,
+                StarknetChainId::TestNet.to_felt(),
+                derive_address,
+            )
+        };
+        let standard_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+
+        // The standard scheme, with the salt added on top.
+        let custom_deploy =
+            new_deploy(|salt, class_hash, constructor_calldata, deployer_address| {
+                Ok(calculate_contract_address(
+                    salt,
+                    class_hash,
+                    constructor_calldata,
+                    deployer_address.clone(),
+                )? + salt)
+            })
+            .unwrap();
+        assert_eq!(
+            custom_deploy.contract_address(),
+            &Address(standard_deploy.contract_address().0 + Felt252::ONE)
+        );
+        // The transaction hash commits to the derived address.
+        assert_ne!(custom_deploy.hash_value(), standard_deploy.hash_value());
+
+        assert_matches!(
+            new_deploy(|_, _, _, _| Err(TransactionError::CustomError(
+                "unsupported scheme".to_string()
+            ))),
+            Err(TransactionError::CustomError(_))
+        );
+    }
+
+    #[test]
+    fn with_nonce_keeps_address_and_recomputes_hash() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        let chain_id = StarknetChainId::TestNet.to_felt();
+
+        let bumped_deploy = internal_deploy.with_nonce(Felt252::ONE, chain_id).unwrap();
+
+        let expected_deploy = DeployAccount::new(
+            ClassHash([2; 32]),
+            VersionSpecificAccountTxFields::new_deprecated(10),
+            Felt252::ONE,
+            Felt252::ONE,
+            vec![],
+            vec![],
+            Felt252::ONE,
+            chain_id,
+        )
+        .unwrap();
+        assert_eq!(bumped_deploy.nonce, Felt252::ONE);
+        assert_eq!(
+            bumped_deploy.contract_address(),
+            internal_deploy.contract_address()
+        );
+        assert_ne!(bumped_deploy.hash_value(), internal_deploy.hash_value());
+        assert_eq!(bumped_deploy.hash_value(), expected_deploy.hash_value());
+    }
+
+    #[test]
+    fn will_charge_fee_normal() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        assert!(internal_deploy.will_charge_fee());
+    }
+
+    #[test]
+    fn will_charge_fee_skip_fee_transfer() {
+        let internal_deploy = DeployAccount {
+            skip_fee_transfer: true,
+            ..deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10))
+        };
+        assert!(!internal_deploy.will_charge_fee());
+    }
+
+    #[test]
+    fn will_charge_fee_zero_max_fee() {
+        let v1_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(0));
+        assert!(!v1_deploy.will_charge_fee());
+
+        let v3_deploy = deploy_account_with_fields(current_account_tx_fields(None, None, 0));
+        assert!(!v3_deploy.will_charge_fee());
+    }
+
+    #[test]
+    fn will_charge_fee_follows_v3_resource_bounds() {
+        let bounds = |max_amount, max_price_per_unit| {
+            Some(ResourceBounds {
+                max_amount,
+                max_price_per_unit,
+            })
+        };
+        let v3_deploy =
+            deploy_account_with_fields(current_account_tx_fields(bounds(0, 0), bounds(0, 0), 0));
+        assert!(!v3_deploy.will_charge_fee());
+
+        let v3_deploy =
+            deploy_account_with_fields(current_account_tx_fields(bounds(0, 0), bounds(10, 1), 0));
+        assert!(v3_deploy.will_charge_fee());
+    }
+
+    #[test]
+    fn requires_funded_balance_normal() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        assert!(internal_deploy.requires_funded_balance());
+    }
+
+    #[test]
+    fn requires_funded_balance_skip_fee_transfer() {
+        let internal_deploy = DeployAccount {
+            skip_fee_transfer: true,
+            ..deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10))
+        };
+        assert!(!internal_deploy.requires_funded_balance());
+    }
+
+    #[test]
+    fn requires_funded_balance_zero_max_fee() {
+        let v1_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(0));
+        assert!(!v1_deploy.requires_funded_balance());
+
+        let v3_deploy = deploy_account_with_fields(current_account_tx_fields(None, None, 0));
+        assert!(!v3_deploy.requires_funded_balance());
+
+        let zero_bounds = || {
+            Some(ResourceBounds {
+                max_amount: 0,
+                max_price_per_unit: 0,
+            })
+        };
+        let v3_deploy =
+            deploy_account_with_fields(current_account_tx_fields(zero_bounds(), zero_bounds(), 0));
+        assert!(!v3_deploy.requires_funded_balance());
+    }
+
+    #[test]
+    fn signature_len_counts_signature_felts() {
+        let unsigned_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        assert_eq!(unsigned_deploy.signature_len(), 0);
+
+        let signed_deploy = DeployAccount {
+            signature: vec![1.into(), 2.into(), 3.into()],
+            ..unsigned_deploy
+        };
+        assert_eq!(signed_deploy.signature_len(), 3);
+    }
+
+    #[test]
+    fn estimate_returns_fee_of_successful_deploy() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        // Neither the max fee nor the missing balance get in the way.
+        let fee = internal_deploy
+            .estimate(
+                &state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let Transaction::DeployAccount(simulated_deploy) =
+            internal_deploy.create_for_simulation(false, false, true, true, false)
+        else {
+            unreachable!()
+        };
+        let tx_info = simulated_deploy
+            .execute(
+                &mut state.create_transactional().unwrap(),
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_ne!(fee, 0);
+        assert_eq!(fee, tx_info.actual_fee);
+        assert!(internal_deploy.address_is_available(&state).unwrap());
+    }
+
+    #[test]
+    fn estimate_fails_on_validation_failure() {
+        // The account checks the transaction signature on `__validate_deploy__`, but none is
+        // provided.
+        let (state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/Account.json", 1.into());
+        let internal_deploy = DeployAccount {
+            constructor_calldata: vec![1.into()],
+            ..internal_deploy
+        };
+
+        assert_matches!(
+            internal_deploy.estimate(
+                &state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            ),
+            Err(TransactionError::ValidationFailed(_))
+        );
+    }
+
+    #[test]
+    fn deployed_class_abi_returns_class_abi() {
+        let (state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/fibonacci.json", 1.into());
+
+        let abi = internal_deploy.deployed_class_abi(&state).unwrap().unwrap();
+
+        let abi: serde_json::Value = serde_json::from_str(&abi).unwrap();
+        assert_eq!(abi[0]["name"], "fib");
+        assert_eq!(abi[0]["inputs"].as_array().unwrap().len(), 3);
+
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let abi = internal_deploy.deployed_class_abi(&state).unwrap();
+        assert!(abi.is_some_and(|abi| abi.contains("__validate_deploy__")));
+    }
+
+    #[test]
+    fn transaction_type_is_deploy_account() {
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        assert_eq!(
+            internal_deploy.transaction_type(),
+            TransactionType::DeployAccount
+        );
+    }
+
+    #[test]
+    fn simulate_all_counts_failures_by_variant() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let deploy = |salt: u64, nonce: u64, max_fee: u128| {
+            DeployAccount::new(
+                *internal_deploy.class_hash(),
+                VersionSpecificAccountTxFields::new_deprecated(max_fee),
+                1.into(),
+                nonce.into(),
+                vec![],
+                vec![],
+                salt.into(),
+                StarknetChainId::TestNet.to_felt(),
+            )
+            .unwrap()
+        };
+
+        let transactions = [
+            deploy(0, 0, 0),
+            // Same address as the first deploy.
+            deploy(0, 1, 0),
+            // Wrong nonce.
+            deploy(1, 1, 0),
+            // The account has no balance to cover the max fee.
+            deploy(2, 0, 10),
+            deploy(3, 0, 0),
+        ];
+
+        let report = DeployAccount::simulate_all(
+            &transactions,
+            &mut state,
+            &BlockContext::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        );
+
+        assert_eq!(report.succeeded, 2);
+        assert_eq!(report.failed(), 3);
+        assert_eq!(
+            report.failures_like(&TransactionError::State(
+                StateError::ContractAddressUnavailable(Address(Felt252::ZERO))
+            )),
+            1
+        );
+        assert_eq!(
+            report.failures_like(&TransactionError::InvalidTransactionNonce(
+                String::new(),
+                String::new()
+            )),
+            1
+        );
+        assert_eq!(
+            report.failures_like(&TransactionError::MaxFeeExceedsBalance(
+                0,
+                Felt252::ZERO,
+                Felt252::ZERO
+            )),
+            1
+        );
+    }
+
+    #[test]
+    fn deploy_account_unhashed_finalize_matches_eager_construction() {
+        let unhashed = DeployAccountUnhashed {
+            class_hash: ClassHash([2; 32]),
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(10),
+            version: Felt252::ONE,
+            nonce: Felt252::ZERO,
+            constructor_calldata: vec![3.into()],
+            signature: vec![4.into()],
+            contract_address_salt: Felt252::ONE,
+        };
+        let eager = DeployAccount::new(
+            ClassHash([2; 32]),
+            VersionSpecificAccountTxFields::new_deprecated(10),
+            Felt252::ONE,
+            Felt252::ZERO,
+            vec![3.into()],
+            vec![4.into()],
+            Felt252::ONE,
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            &unhashed.contract_address().unwrap(),
+            eager.contract_address()
+        );
+        let lazy = unhashed
+            .finalize(StarknetChainId::TestNet.to_felt())
+            .unwrap();
+        assert_eq!(lazy.hash_value(), eager.hash_value());
+        assert_eq!(lazy.signature(), eager.signature());
+    }
+
+    #[test]
+    fn insufficient_balance_fails_execution_by_default() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(10),
+            ..internal_deploy
+        };
+
+        assert_matches!(
+            execute_expecting_error(&state, &internal_deploy, &BlockContext::default()),
+            TransactionError::MaxFeeExceedsBalance(10, ..)
+        );
+    }
+
+    #[test]
+    fn insufficient_balance_reverts_when_enabled() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(10),
+            ..internal_deploy
+        };
+        let mut block_context = BlockContext::default();
+        block_context.set_insufficient_balance_reverts(true);
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(
+            tx_info.revert_error,
+            Some(
+                TransactionError::MaxFeeExceedsBalance(10, Felt252::ZERO, Felt252::ZERO)
+                    .to_string()
+            )
+        );
+        assert!(tx_info.call_info.is_none());
+        assert!(tx_info.validate_info.is_none());
+        // The account has no balance, so no fee could be charged.
+        assert_eq!(tx_info.actual_fee, 0);
+        assert_eq!(
+            State::get_class_hash_at(&mut state, internal_deploy.contract_address()).unwrap(),
+            ClassHash::default()
+        );
+        assert_eq!(
+            State::get_nonce_at(&mut state, internal_deploy.contract_address()).unwrap(),
+            Felt252::ONE
+        );
+    }
+
+    #[test]
+    fn fee_type_override_charges_strk_on_deprecated_fields_tx() {
+        let (mut state, fixture_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let internal_deploy = DeployAccount::new_with_fee_type_override(
+            *fixture_deploy.class_hash(),
+            VersionSpecificAccountTxFields::new_deprecated(1_000_000),
+            1.into(),
+            Felt252::ZERO,
+            vec![],
+            vec![],
+            Felt252::ZERO,
+            StarknetChainId::TestNet.to_felt(),
+            FeeType::Strk,
+        )
+        .unwrap();
+        assert_eq!(internal_deploy.fee_type(), FeeType::Strk);
+
+        // Deploy an ERC20 for each fee token, and fund the account with STRK only.
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        let strk_address = Address(4098.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        state
+            .deploy_contract(strk_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        let initial_balance = Felt252::from(1_000_000);
+        state.set_storage_at(
+            &(strk_address.clone(), balance_low_key.to_bytes_be()),
+            initial_balance,
+        );
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address.clone(), strk_address.clone());
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 2);
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(tx_info.revert_error, None);
+        assert_eq!(tx_info.fee_paid_in, Some(FeeType::Strk));
+        assert_eq!(
+            tx_info.actual_fee,
+            calculate_tx_fee(&tx_info.actual_resources, &block_context, &FeeType::Strk).unwrap()
+        );
+        assert_eq!(
+            tx_info.fee_transfer_info.unwrap().contract_address,
+            strk_address
+        );
+        assert_eq!(
+            State::get_storage_at(&mut state, &(strk_address, balance_low_key.to_bytes_be()))
+                .unwrap(),
+            initial_balance - Felt252::from(tx_info.actual_fee)
+        );
+        assert_eq!(
+            State::get_storage_at(&mut state, &(eth_address, balance_low_key.to_bytes_be()))
+                .unwrap(),
+            Felt252::ZERO
+        );
+    }
+
+    #[test]
+    fn estimate_min_successful_fee_finds_threshold() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        state.set_storage_at(
+            &(eth_address.clone(), balance_low_key.to_bytes_be()),
+            Felt252::from(u64::MAX),
+        );
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address, Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        // The cheapest successful max fee is the actual fee of the deploy.
+        let threshold = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(u64::MAX as u128),
+            ..internal_deploy.clone()
+        }
+        .execute(
+            &mut state.create_transactional().unwrap(),
+            &block_context,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap()
+        .actual_fee;
+
+        let estimate = |candidates: &[u128]| {
+            internal_deploy
+                .estimate_min_successful_fee(
+                    &state,
+                    &block_context,
+                    candidates,
+                    None,
+                    #[cfg(feature = "cairo-native")]
+                    None,
+                )
+                .unwrap()
+        };
+        assert_eq!(
+            estimate(&[threshold * 2, 1, threshold + 1, threshold, threshold - 1]),
+            Some(threshold)
+        );
+        assert_eq!(
+            estimate(&[threshold + 1, threshold * 2]),
+            Some(threshold + 1)
+        );
+        assert_eq!(estimate(&[1, threshold - 1]), None);
+        assert_eq!(estimate(&[]), None);
+        // Max fees above the balance are rejected by the balance check.
+        assert_eq!(estimate(&[u64::MAX as u128 + 1]), None);
+        assert_eq!(
+            estimate(&[threshold, u64::MAX as u128 + 1, u128::MAX]),
+            Some(threshold)
+        );
+
+        // Probing leaves the state untouched.
+        assert!(internal_deploy.address_is_available(&state).unwrap());
+    }
+
+    #[test]
+    fn estimate_min_successful_fee_rejects_v3() {
+        let state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let internal_deploy = deploy_account_with_fields(current_account_tx_fields(
+            Some(ResourceBounds {
+                max_amount: 100,
+                max_price_per_unit: 1,
+            }),
+            None,
+            0,
+        ));
+
+        assert_matches!(
+            internal_deploy.estimate_min_successful_fee(
+                &state,
+                &BlockContext::default(),
+                &[1, 100],
+                None,
+                #[cfg(feature = "cairo-native")]
+                None,
+            ),
+            Err(TransactionError::UnsupportedTxVersion(tx, version, supported))
+            if tx == "DeployAccount" && version == Felt252::THREE && supported == vec![1]
+        );
+    }
+
+    #[test]
+    fn estimate_min_successful_fee_resigns_probes() {
+        let (mut state, unsigned_deploy) =
+            deploy_account_fixture("starknet_programs/Account.json", 1.into());
+        let private_key = FieldElement::from(0x1234_u64);
+        let public_key = Felt252::from_bytes_be(&get_public_key(&private_key).to_bytes_be());
+        let stark_sign = |hash: Felt252| {
+            let message_hash = FieldElement::from_bytes_be(&hash.to_bytes_be()).unwrap();
+            let k = rfc6979_generate_k(&message_hash, &private_key, None);
+            let signature = sign(&private_key, &message_hash, &k).unwrap();
+            vec![
+                Felt252::from_bytes_be(&signature.r.to_bytes_be()),
+                Felt252::from_bytes_be(&signature.s.to_bytes_be()),
+            ]
+        };
+        // The account checks a signature of the transaction hash made with its public key.
+        let mut internal_deploy = DeployAccount::new(
+            *unsigned_deploy.class_hash(),
+            Default::default(),
+            1.into(),
+            0.into(),
+            vec![public_key],
+            Vec::new(),
+            0.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+        internal_deploy.signature = stark_sign(internal_deploy.hash_value);
+
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        state.set_storage_at(
+            &(eth_address.clone(), balance_low_key.to_bytes_be()),
+            Felt252::from(u64::MAX),
+        );
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address, Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        let mut funded_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(u64::MAX as u128),
+            ..internal_deploy.clone()
+        }
+        .with_nonce(Felt252::ZERO, StarknetChainId::TestNet.to_felt())
+        .unwrap();
+        funded_deploy.signature = stark_sign(funded_deploy.hash_value);
+        let threshold = funded_deploy
+            .execute(
+                &mut state.create_transactional().unwrap(),
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap()
+            .actual_fee;
+
+        let estimate = |sign: Option<&dyn Fn(Felt252) -> Vec<Felt252>>| {
+            internal_deploy
+                .estimate_min_successful_fee(
+                    &state,
+                    &block_context,
+                    &[threshold - 1, threshold, threshold * 2],
+                    sign,
+                    #[cfg(feature = "cairo-native")]
+                    None,
+                )
+                .unwrap()
+        };
+        // The signature of the original transaction doesn't match the probes' hashes.
+        assert_eq!(estimate(None), None);
+        assert_eq!(estimate(Some(&stark_sign)), Some(threshold));
+    }
+
+    #[test]
+    fn sequencer_balance_delta_equals_actual_fee() {
+        let (mut state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        internal_deploy.account_tx_fields =
+            VersionSpecificAccountTxFields::new_deprecated(1_000_000);
+
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        state.set_storage_at(
+            &(eth_address.clone(), balance_low_key.to_bytes_be()),
+            Felt252::from(1_000_000),
+        );
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address, Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+        block_context.block_info.sequencer_address = Address(4099.into());
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state.create_transactional().unwrap(),
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx_info.sequencer_balance_delta, None);
+
+        internal_deploy
+            .options_mut()
+            .set_record_sequencer_balance_delta(true);
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx_info.revert_error, None);
+        assert_eq!(tx_info.fee_paid_in, Some(FeeType::Eth));
+        assert_ne!(tx_info.actual_fee, 0);
+        assert_eq!(
+            tx_info.sequencer_balance_delta,
+            Some(Felt252::from(tx_info.actual_fee))
+        );
+        assert_eq!(
+            tx_info.fee_transfer_event(),
+            Some((
+                internal_deploy.contract_address().clone(),
+                Address(4099.into()),
+                tx_info.actual_fee
+            ))
+        );
+    }
+
+    #[test]
+    fn deploy_account_receipt_reports_contract_address_and_fee() {
+        let (mut state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        internal_deploy.account_tx_fields =
+            VersionSpecificAccountTxFields::new_deprecated(1_000_000);
+
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        state.set_storage_at(
+            &(eth_address.clone(), balance_low_key.to_bytes_be()),
+            Felt252::from(1_000_000),
+        );
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address, Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        let receipt = tx_info
+            .to_deploy_account_receipt(
+                *internal_deploy.hash_value(),
+                internal_deploy.contract_address().clone(),
+            )
+            .unwrap();
+
+        assert_ne!(receipt.actual_fee, 0);
+        assert_eq!(receipt.actual_fee, tx_info.actual_fee);
+        assert_eq!(
+            &receipt.contract_address,
+            internal_deploy.contract_address()
+        );
+        assert_eq!(receipt.execution_status, ExecutionStatus::Succeeded);
+        assert_eq!(receipt.transaction_hash, *internal_deploy.hash_value());
+        // The fee transfer emits the only event.
+        assert_eq!(receipt.events.len(), 1);
+    }
+
+    #[test]
+    fn deploy_account_receipt_of_reverted_deploy_keeps_the_address() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(10),
+            ..internal_deploy
+        };
+        let mut block_context = BlockContext::default();
+        block_context.set_insufficient_balance_reverts(true);
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert!(tx_info.call_info.is_none());
+        let receipt = tx_info
+            .to_deploy_account_receipt(
+                *internal_deploy.hash_value(),
+                internal_deploy.contract_address().clone(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            &receipt.contract_address,
+            internal_deploy.contract_address()
+        );
+        assert_eq!(
+            receipt.execution_status,
+            ExecutionStatus::Reverted(
+                TransactionError::MaxFeeExceedsBalance(10, Felt252::ZERO, Felt252::ZERO)
+                    .to_string()
+            )
+        );
+        assert_eq!(receipt.actual_fee, 0);
+        assert!(receipt.events.is_empty());
+    }
+
+    #[test]
+    fn check_fee_balance_sees_pending_deductions() {
+        let (mut state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        internal_deploy.account_tx_fields =
+            VersionSpecificAccountTxFields::new_deprecated(1_000_000);
+
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        state.set_storage_at(
+            &(eth_address.clone(), balance_low_key.to_bytes_be()),
+            Felt252::from(1_000_000),
+        );
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address, Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        // The first transaction of the batch charges its fee to the account's balance.
+        let mut batch_state = state.create_transactional().unwrap();
+        let tx_info = internal_deploy
+            .execute(
+                &mut batch_state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx_info.revert_error, None);
+        assert_ne!(tx_info.actual_fee, 0);
+
+        // A later transaction paying from the same balance sees the pending deduction, while
+        // the committed balance still covers its max fee.
+        let resubmitted_deploy = internal_deploy
+            .with_nonce(Felt252::ONE, StarknetChainId::TestNet.to_felt())
+            .unwrap();
+        assert!(resubmitted_deploy
+            .check_fee_balance(&mut state, &block_context, &FeeType::Eth, None)
+            .is_ok());
+        assert_matches!(
+            resubmitted_deploy.check_fee_balance(
+                &mut batch_state,
+                &block_context,
+                &FeeType::Eth,
+                None
+            ),
+            Err(TransactionError::MaxFeeExceedsBalance(max_fee, balance, _))
+            if max_fee == 1_000_000 && balance == Felt252::from(1_000_000 - tx_info.actual_fee)
+        );
+    }
+
+    #[test]
+    fn validate_result_override_skips_signature_verification() {
+        // The account checks the transaction signature on `__validate_deploy__`, but none is
+        // provided.
+        let (mut state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/Account.json", 1.into());
+        let mut internal_deploy = DeployAccount {
+            constructor_calldata: vec![1.into()],
+            ..internal_deploy
+        };
+        let validate_result = CallInfo {
+            contract_address: internal_deploy.contract_address().clone(),
+            entry_point_selector: Some(*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR),
+            retdata: vec![*VALIDATE_RETDATA],
+            ..Default::default()
+        };
+        internal_deploy
+            .options_mut()
+            .set_validate_result_override(Some(validate_result.clone()));
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(tx_info.revert_error, None);
+        assert_eq!(tx_info.validate_info, Some(validate_result));
+        assert!(tx_info.call_info.is_some());
+        assert!(!internal_deploy.address_is_available(&state).unwrap());
+    }
+
+    #[test]
+    fn invalid_contract_call_retains_source() {
+        let (mut state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/Account.json", 1.into());
+        let mut internal_deploy = DeployAccount {
+            constructor_calldata: vec![1.into()],
+            ..internal_deploy
+        };
+        // `__validate_deploy__` calls into a contract other than the account being deployed.
+        internal_deploy
+            .options_mut()
+            .set_validate_result_override(Some(CallInfo {
+                contract_address: internal_deploy.contract_address().clone(),
+                entry_point_selector: Some(*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR),
+                retdata: vec![*VALIDATE_RETDATA],
+                internal_calls: vec![CallInfo {
+                    contract_address: Address(1.into()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }));
+
+        let result = internal_deploy.execute(
+            &mut state,
+            &BlockContext::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        );
+
+        let Err(TransactionError::ValidationFailed(error)) = result else {
+            panic!("expected a validation failure");
+        };
+        let TransactionError::InvalidContractCall(source) = *error else {
+            panic!("expected an invalid contract call");
+        };
+        assert_matches!(*source, TransactionError::UnauthorizedActionOnValidate);
+    }
+
+    #[test]
+    fn max_events_reverts_constructor_emitting_too_many_events() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_events.json",
+            1.into(),
+        );
+        let internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(u64::MAX as u128),
+            ..internal_deploy
+        };
+
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        let initial_balance = Felt252::from(u64::MAX);
+        state.set_storage_at(
+            &(eth_address.clone(), balance_low_key.to_bytes_be()),
+            initial_balance,
+        );
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address.clone(), Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+        let mut execute = |max_events: Option<usize>| {
+            block_context.set_max_events(max_events);
+            execute_on_copy(&state, &internal_deploy, &block_context).unwrap()
+        };
+
+        // The constructor emits three events.
+        assert_eq!(execute(None).revert_error, None);
+        assert_eq!(execute(Some(3)).revert_error, None);
+
+        let mut state = state.create_transactional().unwrap();
+        block_context.set_max_events(Some(2));
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            tx_info.revert_error,
+            Some(TransactionError::TooManyEvents(3, 2).to_string())
+        );
+        assert!(tx_info.call_info.is_none());
+        assert!(tx_info.fee_transfer_info.is_some());
+        assert!(tx_info.actual_fee > 0);
+        assert_eq!(
+            State::get_storage_at(&mut state, &(eth_address, balance_low_key.to_bytes_be()))
+                .unwrap(),
+            initial_balance - Felt252::from(tx_info.actual_fee)
+        );
+        assert!(internal_deploy.address_is_available(&state).unwrap());
+    }
+
+    #[test]
+    fn class_override_replaces_state_class_for_one_execution() {
+        let (state, mut internal_deploy) =
+            deploy_account_fixture("starknet_programs/fibonacci.json", 1.into());
+        let execute = |internal_deploy: &DeployAccount| {
+            execute_on_copy(&state, internal_deploy, &BlockContext::default())
+        };
+
+        // The class in the state isn't an account.
+        assert_matches!(
+            execute(&internal_deploy),
+            Err(TransactionError::NotAnAccountClass(_))
+        );
+
+        let patched_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/account_without_validation.json").unwrap(),
+        ));
+        internal_deploy
+            .options_mut()
+            .set_class_override(Some(patched_class));
+        let tx_info = execute(&internal_deploy).unwrap();
+        assert_eq!(tx_info.revert_error, None);
+
+        // The class in the state is used again once the override is cleared.
+        internal_deploy.options_mut().set_class_override(None);
+        assert_matches!(
+            execute(&internal_deploy),
+            Err(TransactionError::NotAnAccountClass(_))
+        );
+    }
+
+    #[test]
+    fn max_n_steps_override_allows_expensive_constructor() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation_and_expensive_constructor.json",
+            1.into(),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.validate_max_n_steps = 10;
+        block_context.invoke_tx_max_n_steps = 10;
+
+        assert_matches!(
+            execute_expecting_error(&state, &internal_deploy, &block_context),
+            TransactionError::ConstructorFailed(_)
+        );
+
+        let mut internal_deploy = internal_deploy;
+        internal_deploy
+            .options_mut()
+            .set_max_n_steps_override(Some((
+                DEFAULT_VALIDATE_MAX_N_STEPS,
+                DEFAULT_VALIDATE_MAX_N_STEPS,
+            )));
+        let tx_info = internal_deploy
+            .execute(
+                &mut state.create_transactional().unwrap(),
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx_info.revert_error, None);
+        let constructor_resources = tx_info.call_info.unwrap().execution_resources.unwrap();
+        assert!(constructor_resources.n_steps > 10);
+        // The shared block context is left untouched.
+        assert_eq!(block_context.validate_max_n_steps, 10);
+    }
+
+    #[test]
+    fn required_preconditions_make_deploy_succeed() {
+        let contract =
+            ContractClass::from_path("starknet_programs/account_without_validation.json").unwrap();
+        let class_hash = felt_to_hash(&compute_deprecated_class_hash(&contract).unwrap());
+        let internal_deploy = DeployAccount::new(
+            class_hash,
+            VersionSpecificAccountTxFields::new_deprecated(1_000_000),
+            1.into(),
+            Felt252::ZERO,
+            vec![],
+            vec![],
+            Felt252::ZERO,
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+
+        // The fee token is part of the network, not a precondition of the deploy.
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address.clone(), Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        let preconditions = internal_deploy.required_preconditions(&block_context);
+        assert_eq!(
+            preconditions,
+            Preconditions {
+                class_hash,
+                fee_token_address: eth_address,
+                min_balance: 1_000_000,
+            }
+        );
+
+        // Without the preconditions, the deploy fails.
+        assert!(internal_deploy
+            .execute(
+                &mut state.create_transactional().unwrap(),
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .is_err());
+
+        state
+            .set_contract_class(
+                &preconditions.class_hash,
+                &CompiledClass::Deprecated(Arc::new(contract)),
+            )
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        state.set_storage_at(
+            &(
+                preconditions.fee_token_address,
+                balance_low_key.to_bytes_be(),
+            ),
+            Felt252::from(preconditions.min_balance),
+        );
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx_info.revert_error, None);
+        assert!(!internal_deploy.address_is_available(&state).unwrap());
+    }
+
+    #[test]
+    fn state_write_callback_reports_constructor_writes_in_order() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_storage_writes.json",
+            1.into(),
+        );
+
+        let mut events = Vec::new();
+        internal_deploy
+            .execute_with_state_write_callback(
+                &mut state,
+                &BlockContext::default(),
+                &mut |event| events.push(event),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let contract_address = internal_deploy.contract_address().clone();
+        let storage_write = |key: u64, value: u64| StateWriteEvent::StorageWrite {
+            contract_address: contract_address.clone(),
+            key: Felt252::from(key).to_bytes_be(),
+            value: value.into(),
+        };
+        assert_eq!(
+            events,
+            vec![
+                StateWriteEvent::Deploy {
+                    contract_address: contract_address.clone(),
+                    class_hash: *internal_deploy.class_hash(),
+                },
+                storage_write(1, 10),
+                storage_write(2, 20),
+                storage_write(3, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn storage_overlay_changes_constructor_branch() {
+        let (state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_storage_branch.json",
+            1.into(),
+        );
+        let contract_address = internal_deploy.contract_address().clone();
+        let storage_entry = |key: u64| (contract_address.clone(), Felt252::from(key).to_bytes_be());
+
+        let mut default_state = state.create_transactional().unwrap();
+        internal_deploy
+            .execute(
+                &mut default_state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            State::get_storage_at(&mut default_state, &storage_entry(2)).unwrap(),
+            Felt252::ZERO
+        );
+
+        internal_deploy
+            .options_mut()
+            .set_storage_overlay(HashMap::from([(
+                (contract_address.clone(), Felt252::ONE),
+                Felt252::ONE,
+            )]));
+        let mut overlaid_state = state.create_transactional().unwrap();
+        internal_deploy
+            .execute(
+                &mut overlaid_state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            State::get_storage_at(&mut overlaid_state, &storage_entry(2)).unwrap(),
+            Felt252::ONE
+        );
+        // The overlay value itself isn't persisted.
+        assert_eq!(
+            State::get_storage_at(&mut overlaid_state, &storage_entry(1)).unwrap(),
+            Felt252::ZERO
+        );
+    }
+
+    #[test]
+    fn deploy_non_account_class_should_fail() {
+        let (mut state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/fibonacci.json", 1.into());
+
+        assert_matches!(
+            execute_expecting_error(&state, &internal_deploy, &BlockContext::default()),
+            TransactionError::NotAnAccountClass(class_hash)
+            if &class_hash == internal_deploy.class_hash()
+        );
+
+        // Without validation, the class doesn't need to be an account.
+        let internal_deploy = DeployAccount {
+            skip_validate: true,
+            ..internal_deploy
+        };
+        assert!(internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn deploy_account_trace_populates_all_invocations() {
+        let (mut state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        internal_deploy.account_tx_fields =
+            VersionSpecificAccountTxFields::new_deprecated(1_000_000);
+
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        state.set_storage_at(
+            &(eth_address.clone(), balance_low_key.to_bytes_be()),
+            Felt252::from(1_000_000),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address.clone(), Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        let trace = tx_info.to_deploy_account_trace();
+
+        let validate_invocation = trace.validate_invocation.unwrap();
+        assert_eq!(
+            validate_invocation.entry_point_selector,
+            Some(*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR)
+        );
+        assert_eq!(
+            &validate_invocation.contract_address,
+            internal_deploy.contract_address()
+        );
+        let constructor_invocation = trace.constructor_invocation.unwrap();
+        assert_eq!(
+            constructor_invocation.entry_point_type,
+            Some(EntryPointType::Constructor)
+        );
+        assert_eq!(
+            constructor_invocation,
+            FunctionInvocation::from(tx_info.call_info.as_ref().unwrap())
+        );
+        let fee_transfer_invocation = trace.fee_transfer_invocation.unwrap();
+        assert_eq!(fee_transfer_invocation.contract_address, eth_address);
+        assert_eq!(
+            &fee_transfer_invocation.caller_address,
+            internal_deploy.contract_address()
+        );
+        assert_eq!(trace.execution_resources, tx_info.actual_resources);
+        assert!(trace.execution_resources["n_steps"] > 0);
+    }
+
+    #[test]
+    fn builtin_usage_of_deploy_account() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let builtin_usage = tx_info.builtin_usage();
+        assert!(builtin_usage[&Builtin::Pedersen] > 0);
+        assert_eq!(
+            builtin_usage[&Builtin::RangeCheck],
+            tx_info.actual_resources["range_check_builtin"]
+        );
+        assert!(!builtin_usage.contains_key(&Builtin::Output));
+    }
+
+    #[test]
+    fn expected_compiled_class_hash_is_checked_against_state() {
+        let deploy_with_expected_compiled_class_hash = |expected_compiled_class_hash: u64| {
+            let (mut state, mut internal_deploy) = deploy_account_fixture(
+                "starknet_programs/account_without_validation.json",
+                1.into(),
+            );
+            state
+                .set_compiled_class_hash(
+                    &Felt252::from_bytes_be(&internal_deploy.class_hash().0),
+                    &7.into(),
+                )
+                .unwrap();
+            internal_deploy
+                .options_mut()
+                .set_expected_compiled_class_hash(Some(expected_compiled_class_hash.into()));
+            internal_deploy.execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+        };
+
+        assert!(deploy_with_expected_compiled_class_hash(7).is_ok());
+        assert_matches!(
+            deploy_with_expected_compiled_class_hash(8),
+            Err(TransactionError::InvalidCompiledClassHash(expected, stored))
+            if expected == "8" && stored == "7"
+        );
+    }
+
+    #[cfg(feature = "timings")]
+    #[test]
+    fn execute_records_phase_timings() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation_and_expensive_constructor.json",
+            1.into(),
+        );
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let PhaseTimings {
+            validate,
+            constructor,
+        } = tx_info.phase_timings;
+        assert!(validate.is_some_and(|duration| !duration.is_zero()));
+        assert!(constructor.is_some_and(|duration| !duration.is_zero()));
+    }
+
+    #[test]
+    fn increment_nonce_after_execution_keeps_nonce_of_failed_deploy() {
+        let (state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/fibonacci.json", 1.into());
+        let nonce_after_failed_deploy = |increment_nonce_after_execution: bool| {
+            let mut block_context = BlockContext::default();
+            block_context.set_increment_nonce_after_execution(increment_nonce_after_execution);
+            let mut state = state.create_transactional().unwrap();
+            assert_matches!(
+                internal_deploy.execute(
+                    &mut state,
+                    &block_context,
+                    #[cfg(feature = "cairo-native")]
+                    None,
+                ),
+                Err(TransactionError::NotAnAccountClass(_))
+            );
+            state
+                .get_nonce_at(internal_deploy.contract_address())
+                .unwrap()
+        };
+
+        // Incremented before execution, the nonce is consumed by the failed deploy.
+        assert_eq!(nonce_after_failed_deploy(false), Felt252::ONE);
+        // Incremented after execution, the failed deploy leaves it untouched.
+        assert_eq!(nonce_after_failed_deploy(true), Felt252::ZERO);
+    }
+
+    #[test]
+    fn increment_nonce_after_execution_increments_nonce_of_successful_deploy() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.set_increment_nonce_after_execution(true);
+        let mut state = state.create_transactional().unwrap();
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(tx_info.revert_error, None);
+        assert_eq!(
+            state
+                .get_nonce_at(internal_deploy.contract_address())
+                .unwrap(),
+            Felt252::ONE
+        );
+    }
+
+    #[test]
+    fn dedup_by_address_keeps_highest_fee() {
+        let (_, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let with_max_fee = |deploy: &DeployAccount, max_fee: u128| DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::Deprecated(max_fee),
+            ..deploy.clone()
+        };
+        let other_address_deploy = DeployAccount {
+            contract_address: Address(1.into()),
+            ..internal_deploy.clone()
+        };
+
+        let (kept, dropped) = DeployAccount::dedup_by_address(vec![
+            with_max_fee(&internal_deploy, 10),
+            with_max_fee(&other_address_deploy, 5),
+            with_max_fee(&internal_deploy, 20),
+        ]);
+
+        assert_eq!(kept.len(), 2);
+        assert_eq!(
+            kept[0].contract_address(),
+            internal_deploy.contract_address()
+        );
+        assert_eq!(kept[0].account_tx_fields.max_fee(), 20);
+        assert_eq!(kept[1].contract_address(), &Address(1.into()));
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].account_tx_fields.max_fee(), 10);
+    }
+
+    #[test]
+    fn commitment_leaf_updates_match_applied_state_diff() {
+        let (state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut transactional_state = state.create_transactional().unwrap();
+        let tx_info = internal_deploy
+            .execute(
+                &mut transactional_state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx_info.commitment_leaf_updates, None);
+        let state_diff = StateDiff::from_cached_state(transactional_state.cache()).unwrap();
+
+        internal_deploy
+            .options_mut()
+            .set_record_commitment_leaf_updates(true);
+        let tx_info = internal_deploy
+            .execute(
+                &mut state.create_transactional().unwrap(),
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        let leaf_updates = tx_info.commitment_leaf_updates.unwrap();
+
+        let leaf_update = &leaf_updates[internal_deploy.contract_address()];
+        assert_eq!(leaf_update.class_hash, Some(*internal_deploy.class_hash()));
+        assert_eq!(leaf_update.nonce, Some(Felt252::ONE));
+        assert_eq!(leaf_updates, state_diff.contract_leaf_updates());
+    }
+
+    #[test]
+    fn recorded_gas_prices_override_block_context_gas_prices() {
+        let (mut state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        internal_deploy.account_tx_fields =
+            VersionSpecificAccountTxFields::new_deprecated(1_000_000);
+
+        let erc20_class = ContractClass::from_path("starknet_programs/ERC20.json").unwrap();
+        let erc20_class_hash = felt_to_hash(&compute_deprecated_class_hash(&erc20_class).unwrap());
+        state
+            .set_contract_class(
+                &erc20_class_hash,
+                &CompiledClass::Deprecated(Arc::new(erc20_class)),
+            )
+            .unwrap();
+        let eth_address = Address(4097.into());
+        state
+            .deploy_contract(eth_address.clone(), erc20_class_hash)
+            .unwrap();
+        let (balance_low_key, _) = internal_deploy.fee_balance_storage_key();
+        state.set_storage_at(
+            &(eth_address.clone(), balance_low_key.to_bytes_be()),
+            Felt252::from(1_000_000),
+        );
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(eth_address, Address(4098.into()));
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+        block_context.block_info.sequencer_address = Address(4099.into());
+        let execute = |internal_deploy: &DeployAccount| {
+            execute_on_copy(&state, internal_deploy, &block_context).unwrap()
+        };
+
+        let tx_info = execute(&internal_deploy);
+        assert_eq!(tx_info.revert_error, None);
+        assert_ne!(tx_info.actual_fee, 0);
+
+        internal_deploy
+            .options_mut()
+            .set_recorded_gas_prices(Some(GasPrices::new(2, 0)));
+        let replayed_tx_info = execute(&internal_deploy);
+        assert_eq!(replayed_tx_info.revert_error, None);
+        assert_eq!(replayed_tx_info.actual_fee, 2 * tx_info.actual_fee);
+    }
+
+    #[test]
+    fn explain_address_snapshot() {
+        let internal_deploy = DeployAccount::new(
+            ClassHash::from(Felt252::from(2)),
+            Default::default(),
+            Felt252::ONE,
+            Felt252::ZERO,
+            vec![1.into(), 2.into(), 3.into(), 4.into()],
+            Vec::new(),
+            1.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            internal_deploy.explain_address().unwrap(),
+            AddressDerivation {
+                salt: 1.into(),
+                class_hash: 2.into(),
+                constructor_calldata_hash: Felt252::from_dec_str(
+                    "2904394281987469213428308031512088126582033652660815761074595741628288213124"
+                )
+                .unwrap(),
+                deployer_address: Address(Felt252::ZERO),
+                contract_address: internal_deploy.contract_address().clone(),
+            }
+        );
+    }
+
+    #[test]
+    fn validate_without_deploy_leaves_contract_undeployed() {
+        let (state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        internal_deploy
+            .options_mut()
+            .set_validate_without_deploy(true);
+        let mut state = state.create_transactional().unwrap();
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(tx_info.revert_error, None);
+        assert_eq!(
+            tx_info.validate_info.unwrap().contract_address,
+            *internal_deploy.contract_address()
+        );
+        assert_eq!(tx_info.call_info, None);
+        assert!(!state
+            .cache()
+            .class_hash_writes
+            .contains_key(internal_deploy.contract_address()));
+    }
+
+    #[test]
+    fn storage_access_counts_separate_reads_and_writes() {
+        let (state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_storage_branch.json",
+            1.into(),
+        );
+        // With storage key 1 set, the constructor reads it and writes storage key 2.
+        internal_deploy
+            .options_mut()
+            .set_storage_overlay(HashMap::from([(
+                (internal_deploy.contract_address().clone(), Felt252::ONE),
+                Felt252::ONE,
+            )]));
+        let execute = |internal_deploy: &DeployAccount| {
+            execute_on_copy(&state, internal_deploy, &BlockContext::default()).unwrap()
+        };
+        assert_eq!(execute(&internal_deploy).storage_access_counts, None);
+
+        internal_deploy
+            .options_mut()
+            .set_record_storage_access_counts(true);
+        assert_eq!(
+            execute(&internal_deploy).storage_access_counts,
+            Some(StorageAccessCounts {
+                reads: 1,
+                writes: 1
+            })
+        );
+    }
+
+    #[test]
+    fn post_constructor_state_diff_reflects_constructor_writes() {
+        let (state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_storage_branch.json",
+            1.into(),
+        );
+        // With storage key 1 set, the constructor writes 1 to storage key 2.
+        internal_deploy
+            .options_mut()
+            .set_storage_overlay(HashMap::from([(
+                (internal_deploy.contract_address().clone(), Felt252::ONE),
+                Felt252::ONE,
+            )]));
+        let execute = |internal_deploy: &DeployAccount| {
+            execute_on_copy(&state, internal_deploy, &BlockContext::default()).unwrap()
+        };
+        assert_eq!(execute(&internal_deploy).post_constructor_state_diff, None);
+
+        internal_deploy
+            .options_mut()
+            .set_record_post_constructor_state_diff(true);
+        let state_diff = execute(&internal_deploy)
+            .post_constructor_state_diff
+            .unwrap();
+        let contract_address = internal_deploy.contract_address();
+        assert_eq!(
+            state_diff.address_to_class_hash().get(contract_address),
+            Some(internal_deploy.class_hash())
+        );
+        assert_eq!(
+            state_diff.storage_updates()[contract_address].get(&2.into()),
+            Some(&Felt252::ONE)
+        );
+    }
+
+    #[test]
+    fn deadline_interrupts_expensive_constructor() {
+        let (state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation_and_expensive_constructor.json",
+            1.into(),
+        );
+        let execute = |internal_deploy: &DeployAccount| {
+            execute_on_copy(&state, internal_deploy, &BlockContext::default())
+        };
+
+        internal_deploy
+            .options_mut()
+            .set_deadline(Some(Instant::now()));
+        assert_matches!(execute(&internal_deploy), Err(TransactionError::Timeout));
+
+        internal_deploy
+            .options_mut()
+            .set_deadline(Some(Instant::now() + Duration::from_secs(3600)));
+        assert_matches!(execute(&internal_deploy), Ok(_));
+    }
+
+    #[test]
+    fn with_signature_keeps_address_and_hash() {
+        let (_, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+
+        let signed_deploy = internal_deploy
+            .with_signature(vec![3.into(), 4.into()], StarknetChainId::TestNet.to_felt())
+            .unwrap();
+
+        assert_eq!(
+            signed_deploy.signature(),
+            &[Felt252::from(3), Felt252::from(4)]
+        );
+        assert_eq!(
+            signed_deploy.contract_address(),
+            internal_deploy.contract_address()
+        );
+        assert_eq!(signed_deploy.hash_value(), internal_deploy.hash_value());
+    }
+
+    #[test]
+    fn nonce_after_matches_nonce_of_deployed_account() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut state = state.create_transactional().unwrap();
+        internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(internal_deploy.nonce_after(), Felt252::ONE);
+        assert_eq!(
+            state
+                .get_nonce_at(internal_deploy.contract_address())
+                .unwrap(),
+            internal_deploy.nonce_after()
+        );
+    }
+
+    #[test]
+    fn constructor_calldata_executed_matches_constructor_calldata() {
+        let (mut state, internal_deploy) =
+            deploy_account_fixture("starknet_programs/Account.json", 1.into());
+        let mut internal_deploy = DeployAccount {
+            constructor_calldata: vec![1.into()],
+            ..internal_deploy
+        };
+        // No signature is provided, so the validation is stubbed.
+        internal_deploy
+            .options_mut()
+            .set_validate_result_override(Some(CallInfo {
+                contract_address: internal_deploy.contract_address().clone(),
+                entry_point_selector: Some(*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR),
+                retdata: vec![*VALIDATE_RETDATA],
+                ..Default::default()
+            }));
 
-    #[test]
-    fn get_state_selector() {
-        let path = PathBuf::from("starknet_programs/constructor.json");
-        let contract = ContractClass::from_path(path).unwrap();
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
 
-        let hash = compute_deprecated_class_hash(&contract).unwrap();
-        let class_hash = felt_to_hash(&hash);
+        assert_eq!(
+            tx_info.constructor_calldata_executed(),
+            Some(internal_deploy.constructor_calldata().as_slice())
+        );
+    }
 
-        let block_context = BlockContext::default();
-        let mut _state = CachedState::new(
+    #[test]
+    fn declare_classes_skips_already_declared_class() {
+        let contract_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/account_without_validation.json").unwrap(),
+        ));
+        let (_, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let classes = [(*internal_deploy.class_hash(), contract_class)];
+        let mut state = CachedState::new(
             Arc::new(InMemoryStateReader::default()),
             Arc::new(PermanentContractClassCache::default()),
         );
 
+        assert_eq!(
+            DeployAccount::declare_classes(&mut state, &classes, DuplicateClassPolicy::Skip)
+                .unwrap(),
+            1
+        );
+        // Another batch declaring the same class is a no-op.
+        assert_eq!(
+            DeployAccount::declare_classes(&mut state, &classes, DuplicateClassPolicy::Skip)
+                .unwrap(),
+            0
+        );
+        assert_matches!(
+            DeployAccount::declare_classes(&mut state, &classes, DuplicateClassPolicy::Reject),
+            Err(TransactionError::ClassAlreadyDeclared(_))
+        );
+
+        let tx_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx_info.revert_error, None);
+    }
+
+    #[test]
+    fn custom_chain_id_is_used_from_construction_to_execution() {
+        let custom_chain_id = Felt252::from_bytes_be_slice(b"MY_APPCHAIN");
+        let (state, fixture_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
         let internal_deploy = DeployAccount::new(
-            class_hash,
+            *fixture_deploy.class_hash(),
             Default::default(),
-            0.into(),
-            0.into(),
-            vec![10.into()],
-            Vec::new(),
-            0.into(),
-            StarknetChainId::TestNet2.to_felt(),
+            1.into(),
+            Felt252::ZERO,
+            vec![],
+            vec![],
+            Felt252::ZERO,
+            custom_chain_id,
         )
         .unwrap();
+        assert_ne!(internal_deploy.hash_value(), fixture_deploy.hash_value());
 
-        let state_selector = internal_deploy.get_state_selector(block_context);
+        let mut block_context = BlockContext::default();
+        *block_context.starknet_os_config.chain_id_mut() = custom_chain_id;
+        let tx_info = internal_deploy
+            .execute(
+                &mut state.create_transactional().unwrap(),
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        assert_eq!(tx_info.revert_error, None);
 
-        assert_eq!(
-            state_selector.contract_addresses,
-            vec![internal_deploy.contract_address]
+        // The hash commits to the custom chain id, so other chains reject the transaction.
+        assert_matches!(
+            execute_on_copy(&state, &internal_deploy, &BlockContext::default()),
+            Err(TransactionError::ChainIdMismatch(..))
         );
-        assert_eq!(state_selector.class_hashes, vec![class_hash]);
     }
 
     #[test]
-    fn deploy_account_twice_should_fail() {
-        let path = PathBuf::from("starknet_programs/account_without_validation.json");
-        let contract = ContractClass::from_path(path).unwrap();
+    fn resource_bounds_are_exposed_for_v3_fields_only() {
+        let v1_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(10));
+        assert!(v1_deploy.resource_bounds().is_none());
 
-        let hash = compute_deprecated_class_hash(&contract).unwrap();
-        let class_hash = felt_to_hash(&hash);
+        let v3_deploy = deploy_account_with_fields(current_account_tx_fields(
+            Some(ResourceBounds {
+                max_amount: 100,
+                max_price_per_unit: 10,
+            }),
+            Some(ResourceBounds {
+                max_amount: 0,
+                max_price_per_unit: 0,
+            }),
+            0,
+        ));
+        let resource_bounds = v3_deploy.resource_bounds().unwrap();
+        let l1_gas = resource_bounds.l1_gas.as_ref().unwrap();
+        assert_eq!(l1_gas.max_amount, 100);
+        assert_eq!(l1_gas.max_price_per_unit, 10);
+        assert_eq!(resource_bounds.l2_gas.as_ref().unwrap().max_amount, 0);
+    }
 
-        let block_context = BlockContext::default();
-        let mut state = CachedState::new(
-            Arc::new(InMemoryStateReader::default()),
-            Arc::new(PermanentContractClassCache::default()),
+    #[test]
+    fn preflight_accepts_valid_deploy() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
         );
+        assert_matches!(
+            internal_deploy.preflight(&state, &BlockContext::default()),
+            Ok(())
+        );
+    }
 
-        let internal_deploy = DeployAccount::new(
-            class_hash,
-            Default::default(),
+    #[test]
+    fn preflight_rejects_invalid_nonce() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
             1.into(),
-            0.into(),
-            vec![],
-            Vec::new(),
-            0.into(),
-            StarknetChainId::TestNet2.to_felt(),
-        )
-        .unwrap();
+        );
+        let internal_deploy = DeployAccount {
+            nonce: 5.into(),
+            ..internal_deploy
+        };
+        assert_matches!(
+            internal_deploy.preflight(&state, &BlockContext::default()),
+            Err(TransactionError::InvalidTransactionNonce(..))
+        );
+    }
 
-        let internal_deploy_error = DeployAccount::new(
-            class_hash,
-            Default::default(),
+    #[test]
+    fn preflight_rejects_max_fee_below_minimal_fee() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
             1.into(),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(7, 0);
+        let internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(1),
+            ..internal_deploy
+        };
+        assert_matches!(
+            internal_deploy.preflight(&state, &block_context),
+            Err(TransactionError::MaxFeeTooLow(..))
+        );
+    }
+
+    #[test]
+    fn preflight_rejects_insufficient_balance() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
             1.into(),
-            vec![],
-            Vec::new(),
-            0.into(),
-            StarknetChainId::TestNet2.to_felt(),
-        )
-        .unwrap();
+        );
+        let internal_deploy = DeployAccount {
+            account_tx_fields: VersionSpecificAccountTxFields::new_deprecated(1_000_000),
+            ..internal_deploy
+        };
+        assert_matches!(
+            internal_deploy.preflight(&state, &BlockContext::default()),
+            Err(TransactionError::MaxFeeExceedsBalance(..))
+        );
+    }
 
-        let class_hash = internal_deploy.class_hash();
+    #[test]
+    fn preflight_rejects_unavailable_address() {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
         state
-            .set_contract_class(class_hash, &CompiledClass::Deprecated(Arc::new(contract)))
+            .deploy_contract(
+                internal_deploy.contract_address().clone(),
+                *internal_deploy.class_hash(),
+            )
             .unwrap();
-        internal_deploy
+        assert_matches!(
+            internal_deploy.preflight(&state, &BlockContext::default()),
+            Err(TransactionError::State(
+                StateError::ContractAddressUnavailable(_)
+            ))
+        );
+    }
+
+    #[test]
+    fn preflight_rejects_undeclared_class() {
+        let state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let internal_deploy =
+            deploy_account_with_fields(VersionSpecificAccountTxFields::new_deprecated(0));
+        assert_matches!(
+            internal_deploy.preflight(&state, &BlockContext::default()),
+            Err(TransactionError::State(_))
+        );
+    }
+
+    #[test]
+    fn fee_comparison_returns_v1_and_v3_fees() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 2);
+
+        let comparison = internal_deploy
+            .fee_comparison(
+                &state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_ne!(comparison.v1_fee, 0);
+        assert_eq!(comparison.v3_fee, 2 * comparison.v1_fee);
+        let bounds = comparison.v3_l1_resource_bounds;
+        assert_eq!(bounds.max_price_per_unit, 2);
+        assert!(bounds.max_amount as u128 * bounds.max_price_per_unit >= comparison.v3_fee);
+    }
+
+    #[test]
+    fn with_skip_flags_keeps_a_deploy_account() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+
+        let simulated_deploy: DeployAccount = internal_deploy.with_skip_flags(SkipFlags {
+            skip_validate: true,
+            skip_fee_transfer: true,
+            ignore_max_fee: true,
+            skip_nonce_check: true,
+            ..Default::default()
+        });
+        assert!(simulated_deploy.skip_validate);
+        assert!(!simulated_deploy.skip_execute);
+        assert!(simulated_deploy.skip_fee_transfer);
+        assert!(simulated_deploy.skip_nonce_check);
+        assert_eq!(simulated_deploy.account_tx_fields.max_fee(), u128::MAX);
+        assert_eq!(simulated_deploy.hash_value(), internal_deploy.hash_value());
+
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
+        let tx_info = simulated_deploy
             .execute(
-                &mut state,
+                &mut state.create_transactional().unwrap(),
                 &block_context,
                 #[cfg(feature = "cairo-native")]
                 None,
             )
             .unwrap();
+        assert!(tx_info.validate_info.is_none());
+        assert!(tx_info.call_info.is_some());
+    }
+
+    #[test]
+    fn class_requiring_unavailable_builtin_fails() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let execute =
+            |block_context: &BlockContext| execute_on_copy(&state, &internal_deploy, block_context);
+
+        // The class uses the pedersen and range check builtins.
+        let mut block_context = BlockContext::default();
+        block_context.set_allowed_builtins(Some(HashSet::from([Builtin::Pedersen])));
         assert_matches!(
-            internal_deploy_error
-                .execute(
-                    &mut state,
-                    &block_context,
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
-                .unwrap_err(),
-            TransactionError::State(StateError::ContractAddressUnavailable(..))
-        )
+            execute(&block_context),
+            Err(TransactionError::UnavailableBuiltin(name)) if name == "range_check_builtin"
+        );
+
+        block_context.set_allowed_builtins(Some(HashSet::from([
+            Builtin::Pedersen,
+            Builtin::RangeCheck,
+        ])));
+        assert!(execute(&block_context).is_ok());
     }
 
     #[test]
-    #[should_panic]
-    // Should panic at no calldata for constructor. Error managment not implemented yet.
-    fn deploy_account_constructor_should_fail() {
-        let path = PathBuf::from("starknet_programs/constructor.json");
-        let contract = ContractClass::from_path(path).unwrap();
+    fn account_selectors_include_account_entry_points() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
 
-        let hash = compute_deprecated_class_hash(&contract).unwrap();
-        let class_hash = felt_to_hash(&hash);
+        let selectors = internal_deploy.account_selectors(&state).unwrap();
+        for selector in [
+            &*VALIDATE_ENTRY_POINT_SELECTOR,
+            &*EXECUTE_ENTRY_POINT_SELECTOR,
+            &*VALIDATE_DECLARE_ENTRY_POINT_SELECTOR,
+            &*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR,
+        ] {
+            assert!(selectors.contains(selector));
+        }
+        assert!(!selectors.contains(&*CONSTRUCTOR_ENTRY_POINT_SELECTOR));
+    }
 
-        let block_context = BlockContext::default();
-        let mut state = CachedState::new(
-            Arc::new(InMemoryStateReader::default()),
-            Arc::new(PermanentContractClassCache::default()),
+    #[test]
+    fn syscall_gas_breaks_down_constructor_syscalls() {
+        let (state, mut internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_storage_branch.json",
+            1.into(),
+        );
+        // With storage key 1 set, the constructor reads it and writes storage key 2.
+        internal_deploy
+            .options_mut()
+            .set_storage_overlay(HashMap::from([(
+                (internal_deploy.contract_address().clone(), Felt252::ONE),
+                Felt252::ONE,
+            )]));
+        let execute = |internal_deploy: &DeployAccount| {
+            execute_on_copy(&state, internal_deploy, &BlockContext::default()).unwrap()
+        };
+        assert_eq!(execute(&internal_deploy).syscall_gas, None);
+
+        internal_deploy.options_mut().set_record_syscall_gas(true);
+        assert_eq!(
+            execute(&internal_deploy).syscall_gas,
+            Some(HashMap::from([
+                (Syscall::StorageRead, Syscall::StorageRead.gas_cost()),
+                (Syscall::StorageWrite, Syscall::StorageWrite.gas_cost()),
+            ]))
+        );
+    }
+
+    /// Returns a deploy of `account_constructor_external_call.json` whose constructor calls `fib`
+    /// on a fibonacci contract deployed at address 1111.
+    fn external_call_deploy_fixture() -> (
+        CachedState<InMemoryStateReader, PermanentContractClassCache>,
+        DeployAccount,
+    ) {
+        let (mut state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_constructor_external_call.json",
+            1.into(),
         );
+        let fibonacci = ContractClass::from_path("starknet_programs/fibonacci.json").unwrap();
+        let fibonacci_class_hash =
+            felt_to_hash(&compute_deprecated_class_hash(&fibonacci).unwrap());
+        let fibonacci_address = Address(1111.into());
+        state
+            .set_contract_class(
+                &fibonacci_class_hash,
+                &CompiledClass::Deprecated(Arc::new(fibonacci)),
+            )
+            .unwrap();
+        state
+            .deploy_contract(fibonacci_address.clone(), fibonacci_class_hash)
+            .unwrap();
 
         let internal_deploy = DeployAccount::new(
-            class_hash,
+            *internal_deploy.class_hash(),
             Default::default(),
+            1.into(),
             0.into(),
-            0.into(),
-            Vec::new(),
+            vec![
+                fibonacci_address.0,
+                Felt252::from_bytes_be(&calculate_sn_keccak(b"fib")),
+            ],
             Vec::new(),
             0.into(),
-            StarknetChainId::TestNet2.to_felt(),
+            StarknetChainId::TestNet.to_felt(),
         )
         .unwrap();
 
-        let class_hash = internal_deploy.class_hash();
-        state
-            .set_contract_class(class_hash, &CompiledClass::Deprecated(Arc::new(contract)))
-            .unwrap();
-        internal_deploy
+        (state, internal_deploy)
+    }
+
+    #[test]
+    fn syscall_gas_leaves_out_the_called_entry_point() {
+        let (mut state, mut internal_deploy) = external_call_deploy_fixture();
+        internal_deploy.options_mut().set_record_syscall_gas(true);
+
+        let execution_info = internal_deploy
             .execute(
                 &mut state,
-                &block_context,
+                &BlockContext::default(),
                 #[cfg(feature = "cairo-native")]
                 None,
             )
             .unwrap();
+
+        assert_eq!(
+            execution_info.syscall_gas,
+            Some(HashMap::from([
+                (Syscall::CallContract, Syscall::CallContract.gas_cost()),
+                (Syscall::EmitEvent, Syscall::EmitEvent.gas_cost()),
+            ]))
+        );
+        assert_eq!(
+            Syscall::CallContract.gas_cost(),
+            SYSCALL_GAS_COST["call_contract"] - SYSCALL_GAS_COST["entry_point"]
+        );
     }
 
     #[test]
-    fn deploy_account_wrong_version() {
-        let chain_id = StarknetChainId::TestNet.to_felt();
+    fn format_call_tree_dumps_the_constructor_before_validate() {
+        let (mut state, internal_deploy) = external_call_deploy_fixture();
+        let account_class_hash = *internal_deploy.class_hash();
+        let fib_selector = Felt252::from_bytes_be(&calculate_sn_keccak(b"fib"));
 
-        // declare tx
-        let internal_declare = DeployAccount::new(
-            ClassHash([2; 32]),
-            VersionSpecificAccountTxFields::new_deprecated(9000),
-            2.into(),
-            Felt252::ZERO,
-            vec![],
-            vec![],
-            Felt252::ONE,
-            chain_id,
-        )
-        .unwrap();
-        let result = internal_declare.execute(
-            &mut CachedState::<InMemoryStateReader, PermanentContractClassCache>::default(),
-            &BlockContext::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
+        let execution_info = internal_deploy
+            .execute(
+                &mut state,
+                &BlockContext::default(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        let account_address = internal_deploy.contract_address().0;
+        let class_hash = Felt252::from_bytes_be(&account_class_hash.0);
+        let expected = format!(
+            "\
+constructor:
+  call contract: {account_address}, selector: {constructor}
+    calldata: [1111, {fib_selector}]
+    retdata: []
+    event #0 keys: [1], data: [2]
+    call contract: 1111, selector: {fib_selector}
+      calldata: [1, 1, 10]
+      retdata: [144]
+validate:
+  call contract: {account_address}, selector: {validate_deploy}
+    calldata: [{class_hash}, 0, 1111, {fib_selector}]
+    retdata: []
+",
+            constructor = *CONSTRUCTOR_ENTRY_POINT_SELECTOR,
+            validate_deploy = *VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR,
         );
+        assert_eq!(execution_info.format_call_tree(), expected);
+    }
 
+    #[test]
+    fn identical_fee_token_addresses_are_rejected() {
+        let (state, internal_deploy) = deploy_account_fixture(
+            "starknet_programs/account_without_validation.json",
+            1.into(),
+        );
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.fee_token_address =
+            FeeTokenAddresses::new(Address(4097.into()), Address(4097.into()));
+        let execute =
+            |block_context: &BlockContext| execute_on_copy(&state, &internal_deploy, block_context);
         assert_matches!(
-        result,
-        Err(TransactionError::UnsupportedTxVersion(tx, ver, supp))
-        if tx == "DeployAccount" && ver == 2.into() && supp == vec![1]);
+            execute(&block_context),
+            Err(TransactionError::AmbiguousFeeTokens(address)) if address == Address(4097.into())
+        );
+
+        block_context.set_reject_ambiguous_fee_tokens(false);
+        assert!(execute(&block_context).is_ok());
     }
 }
@@ -6,7 +6,7 @@ use crate::{
     definitions::transaction_type::TransactionType,
     execution::os_usage::OsResources,
     syscalls::syscall_handler_errors::SyscallHandlerError,
-    utils::ClassHash,
+    utils::{Address, ClassHash},
 };
 use cairo_vm::{
     types::{
@@ -103,7 +103,7 @@ pub enum TransactionError {
     #[error("Out of bounds write to a read-only segment.")]
     OutOfBound,
     #[error("Call to another contract has been done")]
-    InvalidContractCall,
+    InvalidContractCall(#[source] Box<TransactionError>),
     #[error("The sender address field in Declare transactions of version 0")]
     InvalidSenderAddress,
     #[error(transparent)]
@@ -164,4 +164,34 @@ pub enum TransactionError {
     DeprecatedAccountTxFieldsVInV3TX,
     #[error("Non V3 Transactions can't be created with non deprecated account tx fields")]
     CurrentAccountTxFieldsInNonV3TX,
+    #[error("V3 Transactions must provide a non-zero L1 gas resource bound")]
+    MissingResourceBounds,
+    #[error("Contract address salt {0} is out of the field range")]
+    SaltOutOfRange(String),
+    #[error(
+        "Transaction hash was computed for chain id {0}, but the block context chain id is {1}"
+    )]
+    ChainIdMismatch(Felt252, Felt252),
+    #[error("Invalid broadcasted transaction: missing or malformed field {0}")]
+    InvalidBroadcastedTxField(String),
+    #[error("The constructor called the account being deployed: {0:?}")]
+    ConstructorSelfCall(Address),
+    #[error("Constructor failure: {0}")]
+    ConstructorFailed(Box<TransactionError>),
+    #[error("Validation failure: {0}")]
+    ValidationFailed(Box<TransactionError>),
+    #[error("The transaction emitted {0} events, exceeding the limit of {1}")]
+    TooManyEvents(usize, usize),
+    #[error("The class requires the builtin {0}, which isn't available in this block")]
+    UnavailableBuiltin(String),
+    #[error("The ETH and STRK fee tokens share the address {0:?}")]
+    AmbiguousFeeTokens(Address),
+    #[error("Transaction reverted: {0}")]
+    TransactionReverted(String),
+    #[error("Counterfactual address {0:?} doesn't match the deployed address {1:?}")]
+    CounterfactualAddressMismatch(Address, Address),
+    #[error("Class {0:?} is not an account class: it has no `__validate_deploy__` entry point")]
+    NotAnAccountClass(ClassHash),
+    #[error("The transaction didn't complete before its deadline")]
+    Timeout,
 }
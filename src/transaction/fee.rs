@@ -28,13 +28,68 @@ use {
 // second element is the actual fee that the transaction uses
 pub type FeeInfo = (Option<CallInfo>, u128);
 
-/// Transfers the amount actual_fee from the caller account to the sequencer.
+/// Estimated L1 gas usage of a transaction and the gas price it was estimated with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub gas: u64,
+    pub gas_price: u128,
+}
+
+/// Rounding applied to the total L1 gas usage of a transaction before multiplying it by the
+/// gas price.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeeRounding {
+    /// Rounds down to the nearest integer.
+    Floor,
+    /// Rounds up to the nearest integer.
+    #[default]
+    Ceil,
+    /// Rounds to the nearest integer, with halfway values rounded away from zero.
+    Round,
+}
+
+impl FeeRounding {
+    fn apply(self, gas_usage: f64) -> f64 {
+        match self {
+            FeeRounding::Floor => gas_usage.floor(),
+            FeeRounding::Ceil => gas_usage.ceil(),
+            FeeRounding::Round => gas_usage.round(),
+        }
+    }
+}
+
+/// Computes the fee of a transaction given its execution resources, allowing alternative fee
+/// models to be plugged into fee charging.
+pub trait FeeStrategy {
+    fn calculate_tx_fee(
+        &self,
+        resources: &HashMap<String, usize>,
+        block_context: &BlockContext,
+        fee_type: &FeeType,
+    ) -> Result<u128, TransactionError>;
+}
+
+/// The default fee model, rounding the L1 gas usage with the given mode.
+impl FeeStrategy for FeeRounding {
+    fn calculate_tx_fee(
+        &self,
+        resources: &HashMap<String, usize>,
+        block_context: &BlockContext,
+        fee_type: &FeeType,
+    ) -> Result<u128, TransactionError> {
+        calculate_tx_fee_with_rounding(resources, block_context, fee_type, *self)
+    }
+}
+
+/// Transfers the amount actual_fee of the `fee_type` token from the caller account to the
+/// sequencer.
 /// Returns the resulting CallInfo of the transfer call.
 pub(crate) fn execute_fee_transfer<S: StateReader, C: ContractClassCache>(
     state: &mut CachedState<S, C>,
     block_context: &BlockContext,
     tx_execution_context: &mut TransactionExecutionContext,
     actual_fee: u128,
+    fee_type: &FeeType,
     #[cfg(feature = "cairo-native")] program_cache: Option<
         Rc<RefCell<ProgramCache<'_, ClassHash>>>,
     >,
@@ -56,7 +111,7 @@ pub(crate) fn execute_fee_transfer<S: StateReader, C: ContractClassCache>(
     .to_vec();
 
     let fee_transfer_call = ExecutionEntryPoint::new(
-        fee_token_address.get_by_fee_type(&FeeType::Eth).clone(),
+        fee_token_address.get_by_fee_type(fee_type).clone(),
         calldata,
         *TRANSFER_ENTRY_POINT_SELECTOR,
         tx_execution_context.account_contract_address.clone(),
@@ -86,10 +141,31 @@ pub(crate) fn execute_fee_transfer<S: StateReader, C: ContractClassCache>(
 /// Calculates the fee of a transaction given its execution resources.
 /// We add the l1_gas_usage (which may include, for example, the direct cost of L2-to-L1
 /// messages) to the gas consumed by Cairo resource and multiply by the L1 gas price.
+///
+/// It doesn't need a transaction, so it can be used to model fees from hand-built resources:
+/// - `"l1_gas_usage"` is required.
+/// - Every other key must be a Cairo resource weighted in the block context's
+///   `cairo_resource_fee_weights`, such as `"n_steps"` or `"pedersen_builtin"`.
+///   Weighted resources missing from the map count as zero.
+///
+/// # Errors
+/// - [TransactionError::FeeError] - If `"l1_gas_usage"` is missing.
+/// - [TransactionError::ResourcesError] - If a key isn't a known resource.
 pub fn calculate_tx_fee(
     resources: &HashMap<String, usize>,
     block_context: &BlockContext,
     fee_type: &FeeType,
+) -> Result<u128, TransactionError> {
+    calculate_tx_fee_with_rounding(resources, block_context, fee_type, FeeRounding::default())
+}
+
+/// Calculates the fee of a transaction given its execution resources, rounding the total L1 gas
+/// usage with `fee_rounding`.
+pub fn calculate_tx_fee_with_rounding(
+    resources: &HashMap<String, usize>,
+    block_context: &BlockContext,
+    fee_type: &FeeType,
+    fee_rounding: FeeRounding,
 ) -> Result<u128, TransactionError> {
     let gas_usage = resources
         .get(&"l1_gas_usage".to_string())
@@ -99,7 +175,7 @@ pub fn calculate_tx_fee(
     let l1_gas_by_cairo_usage = calculate_l1_gas_by_cairo_usage(block_context, resources)?;
     let total_l1_gas_usage = gas_usage.to_f64().unwrap() + l1_gas_by_cairo_usage;
 
-    Ok(total_l1_gas_usage.ceil() as u128
+    Ok(fee_rounding.apply(total_l1_gas_usage) as u128
         * block_context
             .starknet_os_config()
             .gas_price()
@@ -128,7 +204,10 @@ pub(crate) fn calculate_l1_gas_by_cairo_usage(
 }
 
 /// Calculates the maximum weighted value from a given resource usage mapping.
-fn max_of_keys(cairo_rsc: &HashMap<String, usize>, weights: &HashMap<String, f64>) -> f64 {
+pub(crate) fn max_of_keys(
+    cairo_rsc: &HashMap<String, usize>,
+    weights: &HashMap<String, f64>,
+) -> f64 {
     let mut max = 0.0_f64;
     for (k, v) in weights {
         let val = cairo_rsc.get(k).unwrap_or(&0).to_f64().unwrap_or(0.0_f64);
@@ -146,6 +225,8 @@ fn max_of_keys(cairo_rsc: &HashMap<String, usize>, weights: &HashMap<String, f64
 /// - `max_fee`: The maximum fee that the transaction is allowed to charge.
 /// - `tx_execution_context`: The transaction's execution context.
 /// - `skip_fee_transfer`: Whether to skip the fee transfer.
+/// - `fee_strategy`: The strategy used to calculate the actual fee.
+/// - `fee_type`: The token the fee is paid with.
 ///
 /// # Errors
 /// - [TransactionError::ActualFeeExceedsMaxFee] - If the actual fee is bigger than the maximal fee.
@@ -159,6 +240,8 @@ pub fn charge_fee<S: StateReader, C: ContractClassCache>(
     max_fee: u128,
     tx_execution_context: &mut TransactionExecutionContext,
     skip_fee_transfer: bool,
+    fee_strategy: &dyn FeeStrategy,
+    fee_type: &FeeType,
     #[cfg(feature = "cairo-native")] program_cache: Option<
         Rc<RefCell<ProgramCache<'_, ClassHash>>>,
     >,
@@ -167,7 +250,7 @@ pub fn charge_fee<S: StateReader, C: ContractClassCache>(
         return Ok((None, 0));
     }
 
-    let actual_fee = calculate_tx_fee(resources, block_context, &FeeType::Eth)?;
+    let actual_fee = fee_strategy.calculate_tx_fee(resources, block_context, fee_type)?;
 
     let actual_fee = {
         let version_0 = tx_execution_context.version.is_zero();
@@ -190,6 +273,7 @@ pub fn charge_fee<S: StateReader, C: ContractClassCache>(
             block_context,
             tx_execution_context,
             actual_fee,
+            fee_type,
             #[cfg(feature = "cairo-native")]
             program_cache,
         )?)
@@ -201,13 +285,16 @@ pub fn charge_fee<S: StateReader, C: ContractClassCache>(
 #[cfg(test)]
 mod tests {
     use crate::{
-        definitions::block_context::{BlockContext, GasPrices},
+        definitions::block_context::{BlockContext, FeeType, GasPrices},
         execution::TransactionExecutionContext,
         state::{
             cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
             in_memory_state_reader::InMemoryStateReader,
         },
-        transaction::fee::charge_fee,
+        transaction::{
+            error::TransactionError,
+            fee::{calculate_tx_fee, calculate_tx_fee_with_rounding, charge_fee, FeeRounding},
+        },
     };
     use std::{collections::HashMap, sync::Arc};
 
@@ -236,6 +323,8 @@ mod tests {
             max_fee,
             &mut tx_execution_context,
             skip_fee_transfer,
+            &FeeRounding::default(),
+            &FeeType::Eth,
             #[cfg(feature = "cairo-native")]
             None,
         )
@@ -272,6 +361,8 @@ mod tests {
             max_fee,
             &mut tx_execution_context,
             skip_fee_transfer,
+            &FeeRounding::default(),
+            &FeeType::Eth,
             #[cfg(feature = "cairo-native")]
             None,
         )
@@ -279,4 +370,66 @@ mod tests {
 
         assert_eq!(result.1, max_fee);
     }
+
+    /// Tests that each rounding mode is applied to a fractional L1 gas usage.
+    #[test]
+    fn calculate_tx_fee_from_synthetic_resources() {
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(2, 5);
+        let resources = HashMap::from([
+            ("l1_gas_usage".to_string(), 100_usize),
+            ("n_steps".to_string(), 1_000_usize),
+            // 50 * 0.32 = 16 L1 gas, the heaviest Cairo resource.
+            ("pedersen_builtin".to_string(), 50_usize),
+        ]);
+
+        assert_eq!(
+            calculate_tx_fee(&resources, &block_context, &FeeType::Eth).unwrap(),
+            (100 + 16) * 2
+        );
+        assert_eq!(
+            calculate_tx_fee(&resources, &block_context, &FeeType::Strk).unwrap(),
+            (100 + 16) * 5
+        );
+
+        let without_l1_gas = HashMap::from([("n_steps".to_string(), 1_000_usize)]);
+        assert!(matches!(
+            calculate_tx_fee(&without_l1_gas, &block_context, &FeeType::Eth),
+            Err(TransactionError::FeeError(_))
+        ));
+        let with_unknown_resource = HashMap::from([
+            ("l1_gas_usage".to_string(), 100_usize),
+            ("unknown_builtin".to_string(), 1_usize),
+        ]);
+        assert!(matches!(
+            calculate_tx_fee(&with_unknown_resource, &block_context, &FeeType::Eth),
+            Err(TransactionError::ResourcesError)
+        ));
+    }
+
+    #[test]
+    fn calculate_tx_fee_rounding_modes_at_boundary() {
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = GasPrices::new(3, 0);
+        let fee = |n_steps: usize, fee_rounding: FeeRounding| {
+            let resources = HashMap::from([
+                ("l1_gas_usage".to_string(), 10_usize),
+                ("n_steps".to_string(), n_steps),
+            ]);
+            calculate_tx_fee_with_rounding(&resources, &block_context, &FeeType::Eth, fee_rounding)
+                .unwrap()
+        };
+
+        // 10 + 0.25 L1 gas
+        assert_eq!(fee(25, FeeRounding::Floor), 30);
+        assert_eq!(fee(25, FeeRounding::Round), 30);
+        assert_eq!(fee(25, FeeRounding::Ceil), 33);
+        // 10 + 0.75 L1 gas
+        assert_eq!(fee(75, FeeRounding::Floor), 30);
+        assert_eq!(fee(75, FeeRounding::Round), 33);
+        assert_eq!(fee(75, FeeRounding::Ceil), 33);
+        // Integral gas usage is unaffected by the rounding mode.
+        assert_eq!(fee(100, FeeRounding::Floor), 33);
+        assert_eq!(fee(100, FeeRounding::Ceil), 33);
+    }
 }
@@ -1,6 +1,6 @@
 use super::{
     check_account_tx_fields_version,
-    fee::{calculate_tx_fee, charge_fee},
+    fee::{calculate_tx_fee, charge_fee, FeeRounding},
     get_tx_version, ResourceBounds, Transaction, VersionSpecificAccountTxFields,
 };
 use crate::{
@@ -239,7 +239,7 @@ impl InvokeFunction {
         }
 
         let call_info = verify_no_calls_to_other_contracts(&call_info)
-            .map_err(|_| TransactionError::InvalidContractCall)?;
+            .map_err(|err| TransactionError::InvalidContractCall(Box::new(err)))?;
 
         Ok(Some(call_info))
     }
@@ -465,6 +465,8 @@ impl InvokeFunction {
             self.account_tx_fields.max_fee(),
             &mut tx_execution_context,
             self.skip_fee_transfer,
+            &FeeRounding::default(),
+            &FeeType::Eth,
             #[cfg(feature = "cairo-native")]
             program_cache,
         )?;
@@ -567,7 +569,7 @@ impl InvokeFunction {
             account_tx_fields: if ignore_max_fee {
                 if let VersionSpecificAccountTxFields::Current(current) = &self.account_tx_fields {
                     let mut current_fields = current.clone();
-                    current_fields.l1_resource_bounds = Some(ResourceBounds {
+                    current_fields.resource_bounds.l1_gas = Some(ResourceBounds {
                         max_amount: u64::MAX,
                         max_price_per_unit: u128::MAX,
                     });
@@ -387,6 +387,15 @@ mod test {
                 ("l1_gas_usage".to_string(), 18471),
             ]),
             tx_type: Some(TransactionType::L1Handler),
+            fee_overage: None,
+            sequencer_balance_delta: None,
+            commitment_leaf_updates: None,
+            storage_access_counts: None,
+            fee_paid_in: None,
+            post_constructor_state_diff: None,
+            syscall_gas: None,
+            #[cfg(feature = "timings")]
+            phase_timings: Default::default(),
         }
     }
 }
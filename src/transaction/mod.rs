@@ -10,7 +10,7 @@ use crate::{
 pub use declare::Declare;
 pub use declare_v2::DeclareV2;
 pub use deploy::Deploy;
-pub use deploy_account::DeployAccount;
+pub use deploy_account::{DeployAccount, DeployAccountOptions};
 use error::TransactionError;
 pub use invoke_function::InvokeFunction;
 pub use l1_handler::L1Handler;
@@ -203,22 +203,72 @@ fn check_account_tx_fields_version(
     }
 }
 
+/// The flags that relax the execution of a transaction, e.g. to simulate it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SkipFlags {
+    /// The transaction will not be verified.
+    pub skip_validate: bool,
+    /// The transaction will not be executed in the cairo vm.
+    pub skip_execute: bool,
+    /// The transaction will not pay the fee.
+    pub skip_fee_transfer: bool,
+    /// The max fee of the transaction is lifted.
+    pub ignore_max_fee: bool,
+    /// The nonce of the transaction is not checked.
+    pub skip_nonce_check: bool,
+}
+
 #[derive(Clone, Debug)]
 pub enum DataAvailabilityMode {
     L1,
     L2,
 }
 
+impl From<&DataAvailabilityMode> for Felt252 {
+    fn from(mode: &DataAvailabilityMode) -> Self {
+        match mode {
+            DataAvailabilityMode::L1 => Felt252::ZERO,
+            DataAvailabilityMode::L2 => Felt252::ONE,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ResourceBounds {
     pub max_amount: u64,
     pub max_price_per_unit: u128,
 }
 
+impl ResourceBounds {
+    /// Derives L1 gas resource bounds from the max fee of a pre-V3 transaction, e.g. to migrate
+    /// it to V3: the max amount is the gas the max fee buys at `gas_price`, and the max price per
+    /// unit is `gas_price` plus a safety margin of `margin_percent`. The bounds cover the max fee,
+    /// unless the gas amount doesn't fit in a u64.
+    /// A zero gas price is treated as a price of one, so that the bounds aren't zero.
+    pub fn from_eth_max_fee(max_fee: u128, gas_price: u128, margin_percent: u64) -> Self {
+        let gas_price = gas_price.max(1);
+        let max_amount = max_fee / gas_price + u128::from(max_fee % gas_price != 0);
+        let max_price_per_unit = gas_price
+            .saturating_mul(100 + margin_percent as u128)
+            .saturating_add(99)
+            / 100;
+        Self {
+            max_amount: max_amount.try_into().unwrap_or(u64::MAX),
+            max_price_per_unit,
+        }
+    }
+}
+
+/// The gas resource bounds of a V3 transaction, by resource.
+#[derive(Clone, Debug)]
+pub struct ResourceBoundsMapping {
+    pub l1_gas: Option<ResourceBounds>,
+    pub l2_gas: Option<ResourceBounds>,
+}
+
 #[derive(Clone, Debug)]
 pub struct CurrentAccountTxFields {
-    pub l1_resource_bounds: Option<ResourceBounds>,
-    pub l2_resource_bounds: Option<ResourceBounds>,
+    pub resource_bounds: ResourceBoundsMapping,
     pub tip: u64,
     pub nonce_data_availability_mode: DataAvailabilityMode,
     pub fee_data_availability_mode: DataAvailabilityMode,
@@ -243,15 +293,43 @@ impl VersionSpecificAccountTxFields {
     pub fn new_deprecated(max_fee: u128) -> Self {
         Self::Deprecated(max_fee)
     }
+    /// Returns the resource bounds of V3 fields, or `None` for deprecated fields, which only
+    /// carry a max fee.
+    pub fn resource_bounds(&self) -> Option<&ResourceBoundsMapping> {
+        match self {
+            Self::Deprecated(_) => None,
+            Self::Current(current) => Some(&current.resource_bounds),
+        }
+    }
+
     // TODO[0.13]: This method should be removed after completing V3 Tx related changes
     pub(crate) fn max_fee(&self) -> u128 {
         match self {
             Self::Deprecated(max_fee) => *max_fee,
             Self::Current(current) => current
-                .l1_resource_bounds
+                .resource_bounds
+                .l1_gas
                 .as_ref()
                 .map(|rb| rb.max_amount as u128 * rb.max_price_per_unit)
                 .unwrap_or_default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_bounds_from_eth_max_fee_cover_max_fee() {
+        let max_fee = 1_000_003;
+        let bounds = ResourceBounds::from_eth_max_fee(max_fee, 10, 20);
+
+        assert_eq!(bounds.max_amount, 100_001);
+        assert_eq!(bounds.max_price_per_unit, 12);
+        assert!(bounds.max_amount as u128 * bounds.max_price_per_unit >= max_fee);
+
+        let bounds = ResourceBounds::from_eth_max_fee(max_fee, 0, 0);
+        assert!(bounds.max_amount as u128 * bounds.max_price_per_unit >= max_fee);
+    }
+}
@@ -4,7 +4,9 @@ use crate::state::state_api::{State, StateChangesCount};
 use crate::{
     definitions::transaction_type::TransactionType,
     execution::{
-        gas_usage::calculate_tx_gas_usage, os_usage::get_additional_os_resources, CallInfo,
+        gas_usage::calculate_tx_gas_usage,
+        os_usage::{get_additional_os_resources_from, OsResources, DEFAULT_OS_RESOURCES},
+        CallInfo,
     },
     state::ExecutionResourcesManager,
     state::{cached_state::UNINITIALIZED_CLASS_HASH, state_cache::StorageEntry},
@@ -252,6 +254,27 @@ pub fn calculate_tx_resources(
     state_changes: StateChangesCount,
     l1_handler_payload_size: Option<usize>,
     n_reverted_steps: usize,
+) -> Result<HashMap<String, usize>, TransactionError> {
+    calculate_tx_resources_with_os_resources(
+        resources_manager,
+        call_info,
+        tx_type,
+        state_changes,
+        l1_handler_payload_size,
+        n_reverted_steps,
+        &DEFAULT_OS_RESOURCES,
+    )
+}
+
+/// Like [calculate_tx_resources], reading the additional OS resources from `os_resources`.
+pub fn calculate_tx_resources_with_os_resources(
+    resources_manager: ExecutionResourcesManager,
+    call_info: &[Option<CallInfo>],
+    tx_type: TransactionType,
+    state_changes: StateChangesCount,
+    l1_handler_payload_size: Option<usize>,
+    n_reverted_steps: usize,
+    os_resources: &OsResources,
 ) -> Result<HashMap<String, usize>, TransactionError> {
     let non_optional_calls: Vec<CallInfo> = call_info.iter().flatten().cloned().collect();
 
@@ -268,7 +291,8 @@ pub fn calculate_tx_resources(
     let tx_syscall_counter = resources_manager.syscall_counter;
 
     // Add additional Cairo resources needed for the OS to run the transaction.
-    let additional_resources = get_additional_os_resources(tx_syscall_counter, &tx_type)?;
+    let additional_resources =
+        get_additional_os_resources_from(os_resources, tx_syscall_counter, &tx_type)?;
     let new_resources = &cairo_usage + &additional_resources;
     let mut filtered_builtins = new_resources.filter_unused_builtins();
 
@@ -721,7 +745,7 @@ pub mod test_utils {
         pub(crate) static ref TEST_ERC20_CONTRACT_ADDRESS: Address =
         Address(Felt252::from_dec_str("4097").unwrap());
         pub(crate) static ref TEST_STRK_CONTRACT_ADDRESS: Address =
-        Address(Felt252::from_dec_str("4097").unwrap());
+        Address(Felt252::from_dec_str("4098").unwrap());
         pub(crate) static ref TEST_FEE_TOKEN_ADDRESSES : FeeTokenAddresses = FeeTokenAddresses::new(TEST_ERC20_CONTRACT_ADDRESS.clone(), TEST_STRK_CONTRACT_ADDRESS.clone());
 
 
@@ -0,0 +1,56 @@
+// @compile-flags += --account_contract
+
+// A dummy account contract without any validations that stores the caller of its constructor.
+
+%lang starknet
+
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.starknet.common.syscalls import call_contract, get_caller_address
+
+@storage_var
+func constructor_caller() -> (address: felt) {
+}
+
+@constructor
+func constructor{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() {
+    let (caller) = get_caller_address();
+    constructor_caller.write(caller);
+    return ();
+}
+
+@view
+func get_constructor_caller{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}() -> (
+    address: felt
+) {
+    let (address) = constructor_caller.read();
+    return (address=address);
+}
+
+@external
+func __validate_declare__(class_hash: felt) {
+    return ();
+}
+
+@external
+func __validate_deploy__(class_hash: felt, contract_address_salt: felt) {
+    return ();
+}
+
+@external
+func __validate__(contract_address, selector: felt, calldata_len: felt, calldata: felt*) {
+    return ();
+}
+
+@external
+@raw_output
+func __execute__{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    contract_address, selector: felt, calldata_len: felt, calldata: felt*
+) -> (retdata_size: felt, retdata: felt*) {
+    let (retdata_size: felt, retdata: felt*) = call_contract(
+        contract_address=contract_address,
+        function_selector=selector,
+        calldata_size=calldata_len,
+        calldata=calldata,
+    );
+    return (retdata_size=retdata_size, retdata=retdata);
+}
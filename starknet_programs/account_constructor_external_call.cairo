@@ -0,0 +1,60 @@
+// @compile-flags += --account_contract
+
+// A dummy account contract without any validations whose constructor calls `selector` of the
+// contract at `target` with the calldata `(1, 1, 10)` and emits an event.
+
+%lang starknet
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.starknet.common.syscalls import call_contract, emit_event
+
+@constructor
+func constructor{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    target: felt, selector: felt
+) {
+    let (calldata) = alloc();
+    assert calldata[0] = 1;
+    assert calldata[1] = 1;
+    assert calldata[2] = 10;
+    call_contract(
+        contract_address=target, function_selector=selector, calldata_size=3, calldata=calldata
+    );
+    let (keys) = alloc();
+    assert keys[0] = 1;
+    let (data) = alloc();
+    assert data[0] = 2;
+    emit_event(1, keys, 1, data);
+    return ();
+}
+
+@external
+func __validate_declare__(class_hash: felt) {
+    return ();
+}
+
+@external
+func __validate_deploy__(
+    class_hash: felt, contract_address_salt: felt, target: felt, selector: felt
+) {
+    return ();
+}
+
+@external
+func __validate__(contract_address, selector: felt, calldata_len: felt, calldata: felt*) {
+    return ();
+}
+
+@external
+@raw_output
+func __execute__{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    contract_address, selector: felt, calldata_len: felt, calldata: felt*
+) -> (retdata_size: felt, retdata: felt*) {
+    let (retdata_size: felt, retdata: felt*) = call_contract(
+        contract_address=contract_address,
+        function_selector=selector,
+        calldata_size=calldata_len,
+        calldata=calldata,
+    );
+    return (retdata_size=retdata_size, retdata=retdata);
+}
@@ -24,6 +24,10 @@ use starknet_in_rust::{
         state_api::StateReader,
         ExecutionResourcesManager,
     },
+    transaction::{
+        CurrentAccountTxFields, DataAvailabilityMode, ResourceBounds, ResourceBoundsMapping,
+        VersionSpecificAccountTxFields,
+    },
     utils::{Address, ClassHash},
     EntryPointType,
 };
@@ -1401,6 +1405,97 @@ fn test_get_execution_info() {
     assert_eq!(call_info.call_info.unwrap(), expected_call_info);
 }
 
+#[test]
+fn test_get_execution_info_with_current_account_tx_fields() {
+    // The V3 fields are appended after the nonce, so a contract reading the tx info of a V3
+    // transaction still sees the same leading fields.
+    #[cfg(not(feature = "cairo_1_tests"))]
+    let program_data = include_bytes!("../starknet_programs/cairo2/get_execution_info.casm");
+    #[cfg(feature = "cairo_1_tests")]
+    let program_data = include_bytes!("../starknet_programs/cairo1/get_execution_info.casm");
+    let contract_class: CasmContractClass = serde_json::from_slice(program_data).unwrap();
+    let entrypoints = contract_class.clone().entry_points_by_type;
+    let external_entrypoint_selector = &entrypoints.external.get(0).unwrap().selector;
+
+    let contract_class_cache = PermanentContractClassCache::default();
+
+    let address = Address(1111.into());
+    let class_hash: ClassHash = ClassHash([1; 32]);
+
+    contract_class_cache.set_contract_class(
+        class_hash,
+        CompiledClass::Casm {
+            casm: Arc::new(contract_class),
+            sierra: None,
+        },
+    );
+    let mut state_reader = InMemoryStateReader::default();
+    state_reader
+        .address_to_class_hash_mut()
+        .insert(address.clone(), class_hash);
+
+    let mut state = CachedState::new(Arc::new(state_reader), Arc::new(contract_class_cache));
+
+    let block_context = BlockContext::default();
+    // The contract expects a zero max fee, so only the L2 gas bounds are set.
+    let mut tx_execution_context = TransactionExecutionContext::new(
+        Address(0.into()),
+        Felt252::ZERO,
+        vec![22.into(), 33.into()],
+        VersionSpecificAccountTxFields::Current(CurrentAccountTxFields {
+            resource_bounds: ResourceBoundsMapping {
+                l1_gas: None,
+                l2_gas: Some(ResourceBounds {
+                    max_amount: 5,
+                    max_price_per_unit: 6,
+                }),
+            },
+            tip: 7,
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            paymaster_data: vec![8.into()],
+            account_deployment_data: vec![9.into()],
+        }),
+        10.into(),
+        block_context.invoke_tx_max_n_steps(),
+        *TRANSACTION_VERSION,
+    );
+
+    let get_info_exec_entry_point = create_execute_extrypoint(
+        address.clone(),
+        class_hash,
+        external_entrypoint_selector,
+        vec![],
+        EntryPointType::External,
+    );
+
+    let call_info = get_info_exec_entry_point
+        .execute(
+            &mut state,
+            &block_context,
+            &mut ExecutionResourcesManager::default(),
+            &mut tx_execution_context,
+            false,
+            block_context.invoke_tx_max_n_steps(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap()
+        .call_info
+        .unwrap();
+
+    assert!(!call_info.failure_flag);
+    assert_eq!(
+        call_info.retdata,
+        vec![
+            block_context.block_info().sequencer_address.0,
+            0.into(),
+            0.into(),
+            address.0,
+        ]
+    );
+}
+
 #[test]
 fn replace_class_internal() {
     // This test only checks that the contract is updated in the storage, see `replace_class_contract_call`
@@ -4,7 +4,7 @@ use starknet_in_rust::EntryPointType;
 use starknet_in_rust::{
     core::contract_address::compute_deprecated_class_hash,
     definitions::{
-        block_context::StarknetChainId,
+        block_context::{FeeType, StarknetChainId},
         constants::{CONSTRUCTOR_ENTRY_POINT_SELECTOR, VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR},
         transaction_type::TransactionType,
     },
@@ -94,43 +94,46 @@ fn internal_deploy_account() {
 
     assert_eq!(
         tx_info,
-        TransactionExecutionInfo::new(
-            Some(CallInfo {
-                call_type: Some(CallType::Call),
-                contract_address: Address(contract_address),
-                class_hash: Some(class_hash),
-                entry_point_selector: Some(*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR),
-                entry_point_type: Some(EntryPointType::External),
-                calldata: vec![Felt252::from_bytes_be(&class_hash.0), contract_address_salt],
-                execution_resources: Some(ExecutionResources {
-                    n_steps: 13,
-                    n_memory_holes: 0,
+        TransactionExecutionInfo {
+            fee_paid_in: Some(FeeType::Eth),
+            ..TransactionExecutionInfo::new(
+                Some(CallInfo {
+                    call_type: Some(CallType::Call),
+                    contract_address: Address(contract_address),
+                    class_hash: Some(class_hash),
+                    entry_point_selector: Some(*VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR),
+                    entry_point_type: Some(EntryPointType::External),
+                    calldata: vec![Felt252::from_bytes_be(&class_hash.0), contract_address_salt],
+                    execution_resources: Some(ExecutionResources {
+                        n_steps: 13,
+                        n_memory_holes: 0,
+                        ..Default::default()
+                    }),
                     ..Default::default()
                 }),
-                ..Default::default()
-            }),
-            Some(CallInfo {
-                call_type: Some(CallType::Call),
-                contract_address: Address(contract_address),
-                class_hash: Some(class_hash),
-                entry_point_selector: Some(*CONSTRUCTOR_ENTRY_POINT_SELECTOR),
-                entry_point_type: Some(EntryPointType::Constructor),
-                ..Default::default()
-            }),
-            None,
-            None,
-            0,
-            [
-                ("n_steps", 3625),
-                ("pedersen_builtin", 23),
-                ("range_check_builtin", 83),
-                ("l1_gas_usage", 3060)
-            ]
-            .into_iter()
-            .map(|(k, v)| (k.to_string(), v))
-            .collect(),
-            Some(TransactionType::DeployAccount),
-        ),
+                Some(CallInfo {
+                    call_type: Some(CallType::Call),
+                    contract_address: Address(contract_address),
+                    class_hash: Some(class_hash),
+                    entry_point_selector: Some(*CONSTRUCTOR_ENTRY_POINT_SELECTOR),
+                    entry_point_type: Some(EntryPointType::Constructor),
+                    ..Default::default()
+                }),
+                None,
+                None,
+                0,
+                [
+                    ("n_steps", 3625),
+                    ("pedersen_builtin", 23),
+                    ("range_check_builtin", 83),
+                    ("l1_gas_usage", 3060)
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+                Some(TransactionType::DeployAccount),
+            )
+        },
     );
 }
 
@@ -217,7 +220,9 @@ fn internal_deploy_account_cairo1() {
 
     assert_eq!(
         tx_info,
-        TransactionExecutionInfo::new(
+        TransactionExecutionInfo {
+            fee_paid_in: Some(FeeType::Eth),
+            ..TransactionExecutionInfo::new(
             Some(CallInfo {
                 caller_address: Address(0.into()),
                 call_type: Some(CallType::Call),
@@ -302,6 +307,108 @@ fn internal_deploy_account_cairo1() {
             .map(|(k, v)| (k.to_string(), v))
             .collect(),
             Some(TransactionType::DeployAccount),
-        ),
+            )
+        },
+    );
+}
+
+/// Returns a state holding the Cairo 1 `hello_world_account` class and a deploy for it.
+fn cairo1_account_deploy_setup() -> (
+    CachedState<InMemoryStateReader, PermanentContractClassCache>,
+    DeployAccount,
+) {
+    let mut state = CachedState::new(
+        Arc::new(InMemoryStateReader::default()),
+        Arc::new(PermanentContractClassCache::default()),
+    );
+
+    #[cfg(not(feature = "cairo_1_tests"))]
+    let program_data = include_bytes!("../starknet_programs/cairo2/hello_world_account.casm");
+    #[cfg(feature = "cairo_1_tests")]
+    let program_data = include_bytes!("../starknet_programs/cairo1/hello_world_account.casm");
+    let contract_class: CasmContractClass = serde_json::from_slice(program_data).unwrap();
+
+    let class_hash = ClassHash(TEST_ACCOUNT_COMPILED_CONTRACT_CLASS_HASH.to_bytes_be());
+    state
+        .set_contract_class(
+            &class_hash,
+            &CompiledClass::Casm {
+                casm: Arc::new(contract_class),
+                sierra: None,
+            },
+        )
+        .unwrap();
+    state
+        .set_compiled_class_hash(
+            &TEST_ACCOUNT_COMPILED_CONTRACT_CLASS_HASH,
+            &TEST_ACCOUNT_COMPILED_CONTRACT_CLASS_HASH,
+        )
+        .unwrap();
+
+    let internal_deploy_account = DeployAccount::new(
+        class_hash,
+        Default::default(),
+        1.into(),
+        Felt252::ZERO,
+        vec![2.into()],
+        // The account only checks the signature length.
+        vec![3.into(), 4.into()],
+        Felt252::ONE,
+        StarknetChainId::TestNet.to_felt(),
+    )
+    .unwrap();
+
+    (state, internal_deploy_account)
+}
+
+#[test]
+fn deploy_account_cairo1_validate_gas_consumed() {
+    let (mut state, internal_deploy_account) = cairo1_account_deploy_setup();
+
+    let tx_info = internal_deploy_account
+        .execute(
+            &mut state,
+            &Default::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+
+    let validate_gas_consumed = tx_info.validate_gas_consumed().unwrap();
+    assert!(validate_gas_consumed > 0);
+    assert_eq!(
+        Some(validate_gas_consumed),
+        tx_info
+            .validate_info
+            .map(|call_info| call_info.gas_consumed)
     );
 }
+
+#[test]
+fn deploy_account_cairo1_validate_gas_dry_run() {
+    let (mut state, internal_deploy_account) = cairo1_account_deploy_setup();
+
+    let dry_run_gas = internal_deploy_account
+        .validate_gas_dry_run(
+            &state,
+            &Default::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+    // The dry run leaves the state untouched.
+    assert!(internal_deploy_account
+        .address_is_available(&state)
+        .unwrap());
+
+    let tx_info = internal_deploy_account
+        .execute(
+            &mut state,
+            &Default::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+    assert!(dry_run_gas > 0);
+    assert_eq!(Some(dry_run_gas), tx_info.validate_gas_consumed());
+}
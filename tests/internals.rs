@@ -82,7 +82,7 @@ lazy_static! {
     pub static ref TEST_ERC20_CONTRACT_ADDRESS: Address =
     Address(Felt252::from_dec_str("4097").unwrap());
     pub(crate) static ref TEST_STRK_CONTRACT_ADDRESS: Address =
-    Address(Felt252::from_dec_str("4097").unwrap());
+    Address(Felt252::from_dec_str("4098").unwrap());
     pub(crate) static ref TEST_FEE_TOKEN_ADDRESSES : FeeTokenAddresses = FeeTokenAddresses::new(TEST_ERC20_CONTRACT_ADDRESS.clone(), TEST_STRK_CONTRACT_ADDRESS.clone());
 
 
@@ -1779,7 +1779,7 @@ fn test_deploy_account() {
 
     assert_eq!(fee, expected_fee);
 
-    let expected_execution_info = TransactionExecutionInfo::new(
+    let mut expected_execution_info = TransactionExecutionInfo::new(
         expected_validate_call_info.into(),
         expected_execute_call_info.into(),
         None,
@@ -1790,6 +1790,7 @@ fn test_deploy_account() {
         resources,
         TransactionType::DeployAccount.into(),
     );
+    expected_execution_info.fee_paid_in = Some(FeeType::Eth);
     assert_eq!(tx_info, expected_execution_info);
 
     let nonce_from_state = state
@@ -1951,6 +1952,7 @@ fn test_deploy_account_revert() {
     .to_revert_error(format!("Calculated fee ({}) exceeds max fee ({})", fee, max_fee).as_str());
 
     expected_execution_info.set_fee_info(max_fee, expected_fee_transfer_call_info.into());
+    expected_execution_info.fee_paid_in = Some(FeeType::Eth);
 
     assert_eq_sorted!(tx_info, expected_execution_info);
 